@@ -1,11 +1,12 @@
-use crate::client::Client;
+use crate::client::{Client, ClientConfig, ClientHandler};
 use crate::error::Error;
 use crate::transport::stdio::StdioTransport;
-use crate::types::{ClientCapabilities, Implementation};
+use crate::types::{ClientCapabilities, Implementation, ProtocolVersion};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
 use tempfile::NamedTempFile;
 use tokio::process::Command;
 
@@ -24,6 +25,8 @@ pub struct ClientBuilder {
     capabilities: Option<ClientCapabilities>,
     /// Environment variables for the subprocess.
     env: HashMap<String, String>,
+    /// Request timeouts and other settings for the resulting client.
+    config: ClientConfig,
 }
 
 impl ClientBuilder {
@@ -36,6 +39,7 @@ impl ClientBuilder {
             implementation: None,
             capabilities: None,
             env: HashMap::new(),
+            config: ClientConfig::default(),
         }
     }
 
@@ -68,6 +72,7 @@ impl ClientBuilder {
         tracing::trace!(%name, %version, "Setting implementation for ClientBuilder");
         self.implementation = Some(Implementation {
             name: name.to_string(),
+            title: None,
             version: version.to_string(),
         });
         self
@@ -86,6 +91,60 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets the default timeout for requests without a per-method override (30s by default).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        tracing::trace!(?timeout, "Setting default request timeout for ClientBuilder");
+        self.config.request_timeout = timeout;
+        self
+    }
+
+    /// Overrides the request timeout for a single method, e.g. `"tools/call"`.
+    pub fn method_timeout(mut self, method: &str, timeout: Duration) -> Self {
+        tracing::trace!(%method, ?timeout, "Setting method timeout for ClientBuilder");
+        self.config
+            .method_timeouts
+            .insert(method.to_string(), timeout);
+        self
+    }
+
+    /// Limits how many pages the `*_all`/`*_paged` listings follow (100 by default).
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        tracing::trace!(max_pages, "Setting page limit for ClientBuilder");
+        self.config.max_pages = max_pages;
+        self
+    }
+
+    /// Makes the client re-list tools, prompts or resources as soon as the server sends
+    /// the matching `list_changed` notification, keeping `Client::catalog` up to date.
+    pub fn auto_refresh_catalog(mut self, enabled: bool) -> Self {
+        tracing::trace!(enabled, "Setting catalog auto-refresh for ClientBuilder");
+        self.config.auto_refresh_catalog = enabled;
+        self
+    }
+
+    /// Enables checking tool arguments against the tool's `inputSchema` before calling it,
+    /// failing with `Error::InvalidToolArguments` instead of sending invalid calls.
+    pub fn validate_tool_arguments(mut self, enabled: bool) -> Self {
+        tracing::trace!(enabled, "Setting tool argument validation for ClientBuilder");
+        self.config.validate_tool_arguments = enabled;
+        self
+    }
+
+    /// Sets the protocol version proposed during initialization (the latest by default).
+    /// The server may answer with another supported version.
+    pub fn protocol_version(mut self, version: ProtocolVersion) -> Self {
+        tracing::trace!(%version, "Setting protocol version for ClientBuilder");
+        self.config.protocol_version = version;
+        self
+    }
+
+    /// Sets the handler that answers requests sent by the server, such as `roots/list`.
+    pub fn handler(mut self, handler: Arc<dyn ClientHandler>) -> Self {
+        tracing::trace!("Setting client handler for ClientBuilder");
+        self.config.handler = Some(handler);
+        self
+    }
+
     /// Spawns the subprocess using the stored command, arguments, etc.,
     /// creates a `StdioTransport` from the subprocess's stdin/stdout,
     /// then returns a NOT initialized `Client`.
@@ -154,11 +213,17 @@ impl ClientBuilder {
 
         tracing::debug!("Creating StdioTransport");
         let transport = StdioTransport::with_streams(child_stdout, child_stdin)?;
-        let client = Client::new(Arc::new(transport), Some(child), Some(stderr_file));
+        let client = Client::with_config(
+            Arc::new(transport),
+            Some(child),
+            Some(stderr_file),
+            self.config,
+        );
 
         let implementation = self.implementation.unwrap_or_else(|| {
             let default_impl = Implementation {
                 name: "mcp-client".to_string(),
+                title: None,
                 version: "0.1.2".to_string(),
             };
             tracing::debug!(?default_impl, "Using default implementation");
@@ -181,7 +246,7 @@ impl ClientBuilder {
     ///
     /// Returns an error if the command cannot be spawned, or if initialization fails.
     pub async fn spawn_and_initialize(self) -> Result<Client, Error> {
-        let (mut client, implementation, capabilities) = self.spawn().await?;
+        let (client, implementation, capabilities) = self.spawn().await?;
 
         tracing::debug!(?implementation, ?capabilities, "Initializing client");
         client.initialize(implementation, capabilities).await?;
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A cloneable signal used to cancel an in-flight request.
+///
+/// Attach it to a request via `RequestOptions::cancellation`, then call
+/// [`CancellationToken::cancel`] from anywhere to abort the request. The client
+/// stops waiting and sends `notifications/cancelled` to the server.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    reason: Arc<watch::Sender<Option<String>>>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled yet.
+    pub fn new() -> Self {
+        let (reason, _) = watch::channel(None);
+        Self {
+            reason: Arc::new(reason),
+        }
+    }
+
+    /// Cancels the token with the given reason. Only the first call has any effect.
+    pub fn cancel(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        self.reason.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason);
+            true
+        });
+    }
+
+    /// Returns `true` once [`CancellationToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.reason.borrow().is_some()
+    }
+
+    /// Waits until the token is cancelled and returns the reason.
+    pub async fn cancelled(&self) -> String {
+        let mut rx = self.reason.subscribe();
+        let reason = rx
+            .wait_for(|reason| reason.is_some())
+            .await
+            .expect("sender is owned by the token");
+        reason.clone().unwrap_or_default()
+    }
+}
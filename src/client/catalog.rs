@@ -0,0 +1,122 @@
+use jsonschema::Validator;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::broadcast;
+
+use super::{Client, ClientInner, ServerNotification};
+use crate::types::{Prompt, Resource, Tool};
+
+/// The server's tools, prompts and resources as last listed by the client.
+///
+/// Each list is `None` until it is first needed, and again after the server sends
+/// the matching `list_changed` notification. Watch it through `Client::catalog`.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    pub tools: Option<Vec<Tool>>,
+    pub prompts: Option<Vec<Prompt>>,
+    pub resources: Option<Vec<Resource>>,
+    /// Bumped on every invalidation, so a listing that started before one isn't stored.
+    generations: [u64; 3],
+    /// Compiled schemas of the listed tools, compiled on first use and dropped with the
+    /// tools. `None` for a schema the tool doesn't declare or that doesn't compile.
+    validators: HashMap<(String, ToolSchema), Option<Arc<Validator>>>,
+}
+
+/// Which of a tool's schemas a validator checks against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ToolSchema {
+    Input,
+    Output,
+}
+
+/// One of the lists kept in a [`Catalog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CatalogKind {
+    Tools,
+    Prompts,
+    Resources,
+}
+
+impl CatalogKind {
+    /// The catalog list a notification reports as changed, if any.
+    pub(crate) fn changed_by(notification: &ServerNotification) -> Option<Self> {
+        match notification {
+            ServerNotification::ToolListChanged => Some(CatalogKind::Tools),
+            ServerNotification::PromptListChanged => Some(CatalogKind::Prompts),
+            ServerNotification::ResourceListChanged => Some(CatalogKind::Resources),
+            _ => None,
+        }
+    }
+}
+
+impl Catalog {
+    pub(crate) fn generation(&self, kind: CatalogKind) -> u64 {
+        self.generations[kind as usize]
+    }
+
+    /// The compiled `schema` of tool `name`, if it was compiled since the tools were listed.
+    pub(crate) fn validator(
+        &self,
+        name: &str,
+        schema: ToolSchema,
+    ) -> Option<Option<Arc<Validator>>> {
+        self.validators.get(&(name.to_string(), schema)).cloned()
+    }
+
+    pub(crate) fn set_validator(
+        &mut self,
+        name: &str,
+        schema: ToolSchema,
+        validator: Option<Arc<Validator>>,
+    ) {
+        self.validators
+            .insert((name.to_string(), schema), validator);
+    }
+
+    /// Forgets the list of `kind`. Returns whether anything was cached.
+    pub(crate) fn invalidate(&mut self, kind: CatalogKind) -> bool {
+        self.generations[kind as usize] += 1;
+        match kind {
+            CatalogKind::Tools => {
+                self.validators.clear();
+                self.tools.take().is_some()
+            }
+            CatalogKind::Prompts => self.prompts.take().is_some(),
+            CatalogKind::Resources => self.resources.take().is_some(),
+        }
+    }
+}
+
+/// Re-lists whatever the server reports as changed, until the client is dropped.
+pub(crate) async fn refresh_on_change(
+    client: Weak<ClientInner>,
+    mut notifications: broadcast::Receiver<ServerNotification>,
+) {
+    loop {
+        let notification = match notifications.recv().await {
+            Ok(notification) => notification,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "Catalog refresher missed notifications");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Some(kind) = CatalogKind::changed_by(&notification) else {
+            continue;
+        };
+        let Some(inner) = client.upgrade() else {
+            break;
+        };
+        let client = Client { inner };
+        tracing::debug!(?kind, "Refreshing catalog after list_changed notification");
+        let result = match kind {
+            CatalogKind::Tools => client.tools().await.map(drop),
+            CatalogKind::Prompts => client.prompts().await.map(drop),
+            CatalogKind::Resources => client.resources().await.map(drop),
+        };
+        if let Err(e) = result {
+            tracing::warn!(?kind, ?e, "Failed to refresh catalog");
+        }
+    }
+    tracing::debug!("Catalog refresher terminated");
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{CancellationToken, ClientHandler};
+use crate::types::{Progress, ProtocolVersion};
+
+/// Default time to wait for a response when no other timeout applies.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of pages a paginated listing follows before giving up.
+pub const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Settings shared by every request sent through a `Client`.
+///
+/// `ClientBuilder` fills this in for spawned subprocesses; pass it to
+/// `Client::with_config` when wiring a client to your own transport.
+#[derive(Clone)]
+pub struct ClientConfig {
+    /// Timeout applied to requests without a per-method or per-call override.
+    pub request_timeout: Duration,
+    /// Per-method timeout overrides, keyed by method name (e.g. `"tools/call"`).
+    pub method_timeouts: HashMap<String, Duration>,
+    /// Answers requests sent by the server. Without one, only `ping` is answered.
+    pub handler: Option<Arc<dyn ClientHandler>>,
+    /// Maximum number of pages the `*_all`/`*_paged` listings fetch, guarding against
+    /// servers that never stop returning a `nextCursor`.
+    pub max_pages: usize,
+    /// Whether the catalog re-lists tools, prompts or resources as soon as the server
+    /// reports a change, instead of on next use.
+    pub auto_refresh_catalog: bool,
+    /// Whether `call_tool` checks arguments against the tool's `inputSchema` before sending.
+    pub validate_tool_arguments: bool,
+    /// Protocol version proposed in `initialize`.
+    pub protocol_version: ProtocolVersion,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            method_timeouts: HashMap::new(),
+            handler: None,
+            max_pages: DEFAULT_MAX_PAGES,
+            auto_refresh_catalog: false,
+            validate_tool_arguments: false,
+            protocol_version: ProtocolVersion::LATEST,
+        }
+    }
+}
+
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("request_timeout", &self.request_timeout)
+            .field("method_timeouts", &self.method_timeouts)
+            .field("handler", &self.handler.is_some())
+            .field("max_pages", &self.max_pages)
+            .field("auto_refresh_catalog", &self.auto_refresh_catalog)
+            .field("validate_tool_arguments", &self.validate_tool_arguments)
+            .field("protocol_version", &self.protocol_version)
+            .finish()
+    }
+}
+
+impl ClientConfig {
+    /// Returns the timeout to use for `method`, honoring per-method overrides.
+    pub fn timeout_for(&self, method: &str) -> Duration {
+        self.method_timeouts
+            .get(method)
+            .copied()
+            .unwrap_or(self.request_timeout)
+    }
+}
+
+/// Options for a single request, overriding the client's configuration.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Timeout for this request only. Falls back to the client's configuration when `None`.
+    pub timeout: Option<Duration>,
+    /// Token that cancels this request when triggered.
+    pub cancellation: Option<CancellationToken>,
+    /// Receives `notifications/progress` updates for this request. Setting it attaches
+    /// a progress token to the request's `_meta`.
+    pub progress: Option<mpsc::UnboundedSender<Progress>>,
+}
+
+impl RequestOptions {
+    /// Sets the timeout for this request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Lets `token` cancel this request.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Forwards progress updates for this request to `sender`.
+    pub fn progress(mut self, sender: mpsc::UnboundedSender<Progress>) -> Self {
+        self.progress = Some(sender);
+        self
+    }
+}
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+
+use crate::{
+    error::{Error, ErrorCode},
+    protocol::{Request, Response, ResponseError},
+    types::{
+        CreateMessageRequest, CreateMessageResult, ElicitRequest, ElicitResult, ListRootsResult,
+        ProtocolVersion, Versioned,
+    },
+};
+
+/// Trait for answering requests the server sends to the client.
+///
+/// Register an implementation with `ClientBuilder::handler` (or `ClientConfig::handler`).
+/// `ping` is always answered by the client itself; every method left at its default
+/// implementation answers with `MethodNotFound`. Remember to announce the matching
+/// `roots`/`sampling`/`elicitation` capabilities when initializing.
+#[async_trait]
+pub trait ClientHandler: Send + Sync {
+    /// Handle `roots/list`
+    async fn list_roots(&self) -> Result<ListRootsResult, Error> {
+        Err(method_not_found("roots/list"))
+    }
+
+    /// Handle `sampling/createMessage`
+    async fn create_message(
+        &self,
+        _request: CreateMessageRequest,
+    ) -> Result<CreateMessageResult, Error> {
+        Err(method_not_found("sampling/createMessage"))
+    }
+
+    /// Handle `elicitation/create`, asking the user for the requested information
+    async fn elicit(&self, _request: ElicitRequest) -> Result<ElicitResult, Error> {
+        Err(method_not_found("elicitation/create"))
+    }
+
+    /// Handle any other method call
+    async fn handle_method(
+        &self,
+        method: &str,
+        _params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, Error> {
+        Err(method_not_found(method))
+    }
+}
+
+fn method_not_found(method: &str) -> Error {
+    Error::protocol(
+        ErrorCode::MethodNotFound,
+        format!("Method not found: {}", method),
+    )
+}
+
+/// Answers a request from the server, using `handler` for everything except `ping`,
+/// shaping the result for the negotiated protocol `version`.
+pub(crate) async fn respond(
+    handler: Option<&dyn ClientHandler>,
+    request: Request,
+    version: ProtocolVersion,
+) -> Response {
+    let id = request.id.clone();
+    match handle(handler, request, version).await {
+        Ok(result) => Response::success(id, Some(result)),
+        Err(err) => Response::error(id, ResponseError::from(err)),
+    }
+}
+
+async fn handle(
+    handler: Option<&dyn ClientHandler>,
+    request: Request,
+    version: ProtocolVersion,
+) -> Result<serde_json::Value, Error> {
+    if request.method == "ping" {
+        return Ok(serde_json::json!({}));
+    }
+    let Some(handler) = handler else {
+        return Err(method_not_found(&request.method));
+    };
+
+    match request.method.as_str() {
+        "roots/list" => Ok(serde_json::to_value(handler.list_roots().await?)?),
+        "sampling/createMessage" => {
+            let params = parse_params(request.params)?;
+            let result = handler.create_message(params).await?;
+            Ok(serde_json::to_value(result.for_version(version))?)
+        }
+        "elicitation/create" => {
+            let params = parse_params(request.params)?;
+            Ok(serde_json::to_value(handler.elicit(params).await?)?)
+        }
+        method => handler.handle_method(method, request.params).await,
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(
+    params: Option<serde_json::Value>,
+) -> Result<T, Error> {
+    serde_json::from_value(params.unwrap_or_default())
+        .map_err(|e| Error::protocol(ErrorCode::InvalidParams, e.to_string()))
+}
@@ -0,0 +1,76 @@
+use tracing::Level;
+
+use crate::types::{LoggingLevel, LoggingMessageNotification};
+
+/// `tracing` target of events forwarded from `notifications/message`, so server logs
+/// can be filtered separately from the client's own, e.g. `mcp_client_rs::server_log=info`.
+pub const SERVER_LOG_TARGET: &str = "mcp_client_rs::server_log";
+
+/// Maps an MCP (syslog) level onto the closest `tracing` level.
+pub(crate) fn tracing_level(level: LoggingLevel) -> Level {
+    match level {
+        LoggingLevel::Debug => Level::DEBUG,
+        LoggingLevel::Info | LoggingLevel::Notice => Level::INFO,
+        LoggingLevel::Warning => Level::WARN,
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => Level::ERROR,
+    }
+}
+
+/// Re-emits a server log message as a `tracing` event under [`SERVER_LOG_TARGET`].
+pub(crate) fn forward(server: Option<&str>, message: &LoggingMessageNotification) {
+    let server = server.unwrap_or("unknown");
+    let logger = message.logger.as_deref().unwrap_or_default();
+    let text = match &message.data {
+        serde_json::Value::String(text) => text.clone(),
+        data => data.to_string(),
+    };
+
+    macro_rules! emit {
+        ($level:expr) => {
+            tracing::event!(
+                target: SERVER_LOG_TARGET,
+                $level,
+                server,
+                logger,
+                level = %message.level,
+                "{}",
+                text
+            )
+        };
+    }
+    match tracing_level(message.level) {
+        Level::DEBUG => emit!(Level::DEBUG),
+        Level::INFO => emit!(Level::INFO),
+        Level::WARN => emit!(Level::WARN),
+        _ => emit!(Level::ERROR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping() {
+        assert_eq!(tracing_level(LoggingLevel::Debug), Level::DEBUG);
+        assert_eq!(tracing_level(LoggingLevel::Notice), Level::INFO);
+        assert_eq!(tracing_level(LoggingLevel::Warning), Level::WARN);
+        assert_eq!(tracing_level(LoggingLevel::Emergency), Level::ERROR);
+    }
+
+    #[test]
+    fn test_level_wire_format_and_order() {
+        let level: LoggingLevel = serde_json::from_str("\"critical\"").unwrap();
+        assert_eq!(level, LoggingLevel::Critical);
+        assert_eq!(
+            serde_json::to_value(LoggingLevel::Warning).unwrap(),
+            serde_json::json!("warning")
+        );
+        assert!(LoggingLevel::Debug < LoggingLevel::Info);
+        assert!(LoggingLevel::Alert < LoggingLevel::Emergency);
+        assert!(serde_json::from_str::<LoggingLevel>("\"warn\"").is_err());
+    }
+}
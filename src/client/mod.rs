@@ -1,78 +1,237 @@
-use futures::StreamExt;
-use serde_json::Value;
+use futures::future::BoxFuture;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use tempfile::NamedTempFile;
 use tokio::process::Child;
-use tokio::sync::{Mutex, RwLock};
-use tokio::time::{Duration, timeout};
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex, RwLock};
+use tokio::time::{Duration, Instant, timeout};
 
 use crate::{
     ReadResourceResult,
-    error::{Error, ErrorCode},
-    protocol::{Notification, Request, RequestId},
+    error::{Error, ErrorCode, SchemaViolation},
+    protocol::{Notification, Request, RequestId, Response},
     transport::{Message, Transport},
     types::{
         CallToolRequest, CallToolResult, ClientCapabilities, CompleteRequest, CompleteResult,
-        GetPromptResult, Implementation, InitializeResult, ListPromptsResult, ListResourcesResult,
-        ListToolsResult, ServerCapabilities, Tool,
+        CompletionArgument, CompletionReference, Cursor, GetPromptRequest,
+        GetPromptResult, Implementation, InitializeResult, ListPromptsRequest,
+        ListPromptsResult, ListResourceTemplatesRequest, ListResourceTemplatesResult,
+        ListResourcesRequest, ListResourcesResult, ListToolsRequest, ListToolsResult, LoggingLevel,
+        Progress, ProgressToken, Prompt, ProtocolVersion, Resource, ResourceTemplate,
+        ServerCapabilities, SetLevelRequest, Tool, Versioned,
     },
 };
 
 mod builder;
+mod cancellation;
+mod catalog;
+mod config;
+mod handler;
+mod logging;
+mod notifications;
+mod pagination;
+mod subscription;
+mod validation;
 pub use builder::ClientBuilder;
+pub use cancellation::CancellationToken;
+pub use catalog::Catalog;
+use catalog::{CatalogKind, ToolSchema};
+pub use config::{ClientConfig, DEFAULT_MAX_PAGES, DEFAULT_REQUEST_TIMEOUT, RequestOptions};
+pub use handler::ClientHandler;
+pub use logging::SERVER_LOG_TARGET;
+pub use notifications::{NotificationHandler, ServerNotification};
+pub use pagination::Paged;
+pub use subscription::ResourceSubscription;
 
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
+
+/// Table of in-flight requests, keyed by request ID, each waiting on its own response.
+/// Set to `None` by the dispatcher once the transport stream has ended.
+type PendingRequests = Arc<std::sync::Mutex<Option<HashMap<RequestId, oneshot::Sender<Response>>>>>;
+
+/// Progress listeners, keyed by the progress token attached to their request.
+type ProgressListeners = Arc<std::sync::Mutex<HashMap<ProgressToken, mpsc::UnboundedSender<Progress>>>>;
+
+/// Notification handlers registered per method.
+type NotificationHandlers = Arc<std::sync::RwLock<HashMap<String, Vec<NotificationHandler>>>>;
+
+/// How many notifications a lagging `Client::notifications` subscriber may fall behind.
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 100;
+
+/// A stream of progress updates for a single request. Ends once the request completes.
+pub type ProgressStream = Pin<Box<dyn Stream<Item = Progress> + Send>>;
+
+/// A stream of every notification the server sends.
+pub type NotificationStream = Pin<Box<dyn Stream<Item = ServerNotification> + Send>>;
+
+/// Routing tables shared between request callers and the background dispatcher.
+#[derive(Clone)]
+struct Routes {
+    /// Requests that have been sent and are still waiting for a response.
+    pending: PendingRequests,
+    /// Requests that asked to be told about progress.
+    progress: ProgressListeners,
+    /// Callbacks registered for specific notification methods.
+    handlers: NotificationHandlers,
+    /// Fan-out of every notification to `Client::notifications` subscribers.
+    notifications: broadcast::Sender<ServerNotification>,
+    /// Server identity from `initialize`, used to label forwarded log messages.
+    server_info: Arc<std::sync::RwLock<Option<Implementation>>>,
+    /// The protocol version agreed on during `initialize`, used to shape answers to the server.
+    protocol_version: Arc<std::sync::RwLock<Option<ProtocolVersion>>>,
+    /// Cached listings, invalidated by `list_changed` notifications.
+    catalog: Arc<watch::Sender<Catalog>>,
+    /// Live `ResourceSubscription`s per URI, so only the last one unsubscribes.
+    subscriptions: Arc<std::sync::Mutex<HashMap<String, usize>>>,
+}
+
+impl Routes {
+    fn new() -> Self {
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY);
+        Self {
+            pending: Arc::new(std::sync::Mutex::new(Some(HashMap::new()))),
+            progress: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            handlers: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            notifications,
+            server_info: Arc::new(std::sync::RwLock::new(None)),
+            protocol_version: Arc::new(std::sync::RwLock::new(None)),
+            catalog: Arc::new(watch::Sender::new(Catalog::default())),
+            subscriptions: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+}
 
 /// The MCP client struct, managing transport, requests, and responses.
 /// This client is suitable for connecting to an MCP-compliant server to
 /// send requests, receive responses, and handle notifications.
+///
+/// Requests only need `&self`, so several of them can be in flight at once;
+/// a background dispatcher routes each response to the caller waiting on its ID.
+///
+/// `Client` is a cheap, `Send + Sync` handle: cloning it shares the same transport,
+/// capabilities and pending-request table, so clones can be moved into separate
+/// tasks. The subprocess (if any) is shut down when the last clone is dropped.
+#[derive(Clone)]
 pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+/// State shared by all clones of a [`Client`].
+struct ClientInner {
     /// The transport over which messages are sent/received.
     transport: Arc<dyn Transport>,
     /// The server's capabilities, populated after a successful initialize call.
     server_capabilities: Arc<RwLock<Option<ServerCapabilities>>>,
     /// Request ID counter to generate unique IDs for each request.
-    request_counter: Arc<RwLock<i64>>,
-    /// An MPSC receiver for reading incoming responses from the transport.
-    response_receiver: Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Message>>>,
-    /// An MPSC sender for sending responses from the transport handler to this client.
-    response_sender: tokio::sync::mpsc::UnboundedSender<Message>,
+    request_counter: AtomicI64,
+    /// In-flight requests and progress listeners, shared with the dispatcher.
+    routes: Routes,
+    /// Timeouts and other settings applied to every request.
+    config: ClientConfig,
     /// To handle shutdown, in stdin/stdout case we also need to shut down subprocess
-    subprocess: Option<tokio::process::Child>,
+    subprocess: Mutex<Option<tokio::process::Child>>,
     /// Temporary file for stderr output - will be automatically deleted when dropped
     stderr_file: Option<NamedTempFile>,
 }
 
+/// Removes a request from the pending table when its caller stops waiting,
+/// whether it got a response, timed out, or was dropped.
+///
+/// If the request was sent but never answered, the server is told to stop
+/// working on it with a `notifications/cancelled` carrying `cancel_reason`.
+struct PendingGuard {
+    routes: Routes,
+    id: RequestId,
+    /// Progress token registered for this request, if any.
+    progress_token: Option<ProgressToken>,
+    transport: Arc<dyn Transport>,
+    /// Reason sent to the server on drop; `None` if no cancellation should be sent.
+    cancel_reason: Option<String>,
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        if let Some(token) = &self.progress_token {
+            self.routes.progress.lock().unwrap().remove(token);
+        }
+        let still_pending = self
+            .routes
+            .pending
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|map| map.remove(&self.id))
+            .is_some();
+
+        let Some(reason) = self.cancel_reason.take() else {
+            return;
+        };
+        if !still_pending {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!(id = %self.id, "No runtime available to send cancellation");
+            return;
+        };
+
+        let transport = self.transport.clone();
+        let params = serde_json::json!({ "requestId": self.id, "reason": reason });
+        tracing::debug!(?params, "Cancelling MCP request");
+        runtime.spawn(async move {
+            let notification = Notification::new("notifications/cancelled", Some(params));
+            if let Err(e) = transport.send(Message::Notification(notification)).await {
+                tracing::warn!(?e, "Failed to send cancellation notification");
+            }
+        });
+    }
+}
+
 impl Client {
     /// Creates a new MCP client with the given transport.
     /// This does not perform initialization. You typically call `client.initialize(...)` next.
     pub fn new(transport: Arc<dyn Transport>, subprocess: Option<Child>, stderr_file: Option<NamedTempFile>) -> Self {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        Self::with_config(transport, subprocess, stderr_file, ClientConfig::default())
+    }
+
+    /// Creates a new MCP client with the given transport and configuration.
+    /// This does not perform initialization. You typically call `client.initialize(...)` next.
+    pub fn with_config(
+        transport: Arc<dyn Transport>,
+        subprocess: Option<Child>,
+        stderr_file: Option<NamedTempFile>,
+        config: ClientConfig,
+    ) -> Self {
+        let routes = Routes::new();
+        let handler = config.handler.clone();
+        let auto_refresh_catalog = config.auto_refresh_catalog;
         let client = Self {
-            transport: transport.clone(),
-            server_capabilities: Arc::new(RwLock::new(None)),
-            request_counter: Arc::new(RwLock::new(0)),
-            response_receiver: Arc::new(Mutex::new(rx)),
-            response_sender: tx.clone(),
-            subprocess,
-            stderr_file,
+            inner: Arc::new(ClientInner {
+                transport: transport.clone(),
+                server_capabilities: Arc::new(RwLock::new(None)),
+                request_counter: AtomicI64::new(0),
+                routes: routes.clone(),
+                config,
+                subprocess: Mutex::new(subprocess),
+                stderr_file,
+            }),
         };
 
-        // Spawn a task to forward all transport messages into our MPSC channel.
+        // Spawn a task that dispatches each incoming message to whoever is waiting on it.
+        // Subscribe before spawning so messages arriving right after construction aren't missed.
+        let mut stream = transport.receive();
         let transport_clone = transport.clone();
-        let tx_clone = tx.clone();
         tokio::spawn(async move {
-            tracing::debug!("Starting response handler task");
-            let mut stream = transport_clone.receive();
+            tracing::debug!("Starting response dispatcher task");
             while let Some(result) = stream.next().await {
                 match result {
                     Ok(message) => {
                         tracing::trace!(?message, "Received message from transport");
-                        if tx_clone.send(message).is_err() {
-                            tracing::error!("Failed to forward message - channel closed");
-                            break;
-                        }
+                        Self::dispatch(&routes, &transport_clone, &handler, message);
                     }
                     Err(e) => {
                         tracing::error!(?e, "Error receiving message from transport");
@@ -80,13 +239,109 @@ impl Client {
                     }
                 }
             }
-            tracing::debug!("Response handler task terminated");
+            // Dropping the senders wakes every waiter with a "connection closed" error.
+            routes.pending.lock().unwrap().take();
+            tracing::debug!("Response dispatcher task terminated");
         });
 
+        if auto_refresh_catalog {
+            let notifications = client.inner.routes.notifications.subscribe();
+            tokio::spawn(catalog::refresh_on_change(
+                Arc::downgrade(&client.inner),
+                notifications,
+            ));
+        }
+
         tracing::debug!("Created new MCP client");
         client
     }
 
+    /// Routes a single incoming message from the transport.
+    fn dispatch(
+        routes: &Routes,
+        transport: &Arc<dyn Transport>,
+        handler: &Option<Arc<dyn ClientHandler>>,
+        message: Message,
+    ) {
+        match message {
+            Message::Response(response) => {
+                let sender = routes
+                    .pending
+                    .lock()
+                    .unwrap()
+                    .as_mut()
+                    .and_then(|map| map.remove(&response.id));
+                match sender {
+                    Some(sender) => {
+                        tracing::debug!(?response, "Received matching MCP response");
+                        let _ = sender.send(response);
+                    }
+                    None => {
+                        tracing::debug!(?response, "Received response for unknown request");
+                    }
+                }
+            }
+            Message::Notification(notif) => {
+                tracing::debug!(?notif, "Received notification");
+                let method = notif.method.clone();
+                let notification = ServerNotification::from(notif);
+
+                match &notification {
+                    ServerNotification::Progress(update) => {
+                        let listeners = routes.progress.lock().unwrap();
+                        if let Some(listener) = listeners.get(&update.progress_token) {
+                            let _ = listener.send(update.progress.clone());
+                        }
+                    }
+                    ServerNotification::LoggingMessage(message) => {
+                        let server_info = routes.server_info.read().unwrap();
+                        let server = server_info.as_ref().map(|info| info.name.as_str());
+                        logging::forward(server, message);
+                    }
+                    _ => {
+                        if let Some(kind) = CatalogKind::changed_by(&notification) {
+                            tracing::debug!(?kind, "Invalidating catalog");
+                            routes.catalog.send_if_modified(|catalog| catalog.invalidate(kind));
+                        }
+                    }
+                }
+
+                // Clone the handlers out so they can register further handlers without deadlocking.
+                let handlers = routes
+                    .handlers
+                    .read()
+                    .unwrap()
+                    .get(&method)
+                    .cloned()
+                    .unwrap_or_default();
+                for handler in handlers {
+                    handler(&notification);
+                }
+
+                // An error only means nobody is subscribed right now.
+                let _ = routes.notifications.send(notification);
+            }
+            Message::Request(req) => {
+                tracing::debug!(?req, "Received request from server");
+                // Answer in a separate task so a slow handler doesn't hold up responses.
+                let transport = transport.clone();
+                let handler = handler.clone();
+                let version = routes
+                    .protocol_version
+                    .read()
+                    .unwrap()
+                    .unwrap_or(ProtocolVersion::LATEST);
+                tokio::spawn(async move {
+                    let response = handler::respond(handler.as_deref(), req, version).await;
+                    tracing::debug!(?response, "Answering server request");
+                    if let Err(e) = transport.send(Message::Response(response)).await {
+                        tracing::error!(?e, "Failed to answer server request");
+                    }
+                });
+            }
+        }
+    }
+
     /// Initializes the client by sending an "initialize" request containing:
     /// - client implementation info
     /// - client capabilities
@@ -95,16 +350,17 @@ impl Client {
     /// On success, updates the client's `server_capabilities` field and sends an
     /// `initialized` notification to the server.
     pub async fn initialize(
-        &mut self,
+        &self,
         implementation: Implementation,
         capabilities: ClientCapabilities,
     ) -> Result<InitializeResult, Error> {
         tracing::info!(?implementation, "Initializing MCP client");
 
+        let requested = self.inner.config.protocol_version;
         let params = serde_json::json!({
-            "clientInfo": implementation,
-            "capabilities": capabilities,
-            "protocolVersion": crate::LATEST_PROTOCOL_VERSION,
+            "clientInfo": implementation.for_version(requested),
+            "capabilities": capabilities.for_version(requested),
+            "protocolVersion": requested.as_str(),
         });
 
         let response = self.request("initialize", Some(params)).await?;
@@ -112,8 +368,24 @@ impl Client {
 
         tracing::debug!(?init_result, "Received initialization response");
 
-        // Store the server capabilities.
-        *self.server_capabilities.write().await = Some(init_result.capabilities.clone());
+        // The server answers with our version if it supports it, or another one it prefers.
+        let negotiated: ProtocolVersion = match init_result.protocol_version.parse() {
+            Ok(version) => version,
+            Err(e) => {
+                tracing::error!(
+                    %requested,
+                    received = %init_result.protocol_version,
+                    "Server negotiated an unsupported protocol version"
+                );
+                return Err(e);
+            }
+        };
+        tracing::debug!(version = %negotiated, "Negotiated protocol version");
+        *self.inner.routes.protocol_version.write().unwrap() = Some(negotiated);
+
+        // Store the server capabilities and identity.
+        *self.inner.server_capabilities.write().await = Some(init_result.capabilities.clone());
+        *self.inner.routes.server_info.write().unwrap() = Some(init_result.server_info.clone());
 
         // After initialization completes, send the `initialized` notification.
         tracing::debug!("Sending initialized notification");
@@ -124,95 +396,179 @@ impl Client {
     }
 
     /// Sends a request to the server with the given method and optional parameters,
-    /// then waits up to 30 seconds for a matching response.
+    /// then waits for a matching response, using the client's configured timeout.
+    ///
+    /// Multiple requests may be awaited concurrently on the same client.
     ///
     /// # Errors
     ///
     /// Returns an error if the transport fails, the server returns an error,
-    /// or no response is received within 30 seconds.
+    /// or no response is received before the timeout (`Error::Timeout`).
     pub async fn request(
-        &mut self,
+        &self,
         method: &str,
         params: Option<serde_json::Value>,
     ) -> Result<serde_json::Value, Error> {
-        // Increment request ID
-        let mut counter = self.request_counter.write().await;
-        *counter += 1;
-        let id = RequestId::Number(*counter);
+        self.request_with_options(method, params, RequestOptions::default())
+            .await
+    }
 
-        let request = Request::new(method, params, id.clone());
-        tracing::debug!(?request, "Sending MCP request");
+    /// Like [`Client::request`], but with per-request options such as a custom timeout.
+    pub async fn request_with_options(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+        options: RequestOptions,
+    ) -> Result<serde_json::Value, Error> {
+        let number = self.inner.request_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        let id = RequestId::Number(number);
+        let request_timeout = options
+            .timeout
+            .unwrap_or_else(|| self.inner.config.timeout_for(method));
 
-        // Send request
-        self.transport.send(Message::Request(request)).await?;
+        // Register the waiter before sending so a fast response can't be missed.
+        let (tx, rx) = oneshot::channel();
+        match self.inner.routes.pending.lock().unwrap().as_mut() {
+            Some(map) => {
+                map.insert(id.clone(), tx);
+            }
+            None => {
+                return Err(Error::protocol(
+                    ErrorCode::InternalError,
+                    "Connection closed, cannot send request",
+                ));
+            }
+        }
+        let mut guard = PendingGuard {
+            routes: self.inner.routes.clone(),
+            id: id.clone(),
+            progress_token: None,
+            transport: self.inner.transport.clone(),
+            cancel_reason: None,
+        };
+
+        // Ask the server for progress updates by attaching a token to the request's `_meta`.
+        let mut params = params;
+        if let Some(listener) = options.progress.clone() {
+            let token = ProgressToken::Number(number);
+            let params = params.get_or_insert_with(|| serde_json::json!({}));
+            if let Some(object) = params.as_object_mut() {
+                let meta = object
+                    .entry("_meta")
+                    .or_insert_with(|| serde_json::json!({}));
+                meta["progressToken"] = serde_json::to_value(&token)?;
+                self.inner
+                    .routes
+                    .progress
+                    .lock()
+                    .unwrap()
+                    .insert(token.clone(), listener);
+                guard.progress_token = Some(token);
+            }
+        }
+
+        let request = Request::new(method, params, id);
+        tracing::debug!(?request, ?request_timeout, "Sending MCP request");
+
+        // Giving up on the request, even while it is still being sent, should tell the
+        // server about it. The spec forbids cancelling `initialize`.
+        if method != "initialize" {
+            guard.cancel_reason = Some("Request cancelled by client".to_string());
+        }
+        let cancellation = options.cancellation.clone().unwrap_or_default();
+
+        // Send in a separate task, so the timeout and cancellation below also cover
+        // transports whose send lasts until the server answers, while a message that is
+        // half written when the caller gives up still gets finished.
+        let started = Instant::now();
+        let transport = self.inner.transport.clone();
+        let sending =
+            tokio::spawn(async move { transport.send(Message::Request(request)).await });
+        let exchange = async {
+            sending
+                .await
+                .map_err(|e| Error::Transport(format!("Sending request failed: {}", e)))??;
+            Ok::<_, Error>(rx.await)
+        };
 
-        // Wait for a matching response (by request ID) or a 30s timeout
-        let mut rx = self.response_receiver.lock().await;
-        
         tokio::select! {
-            // Branch 1: Handle the message receiving logic
-            result = async {
-                while let Some(message) = rx.recv().await {
-                    match message {
-                        Message::Response(response) if response.id == id => {
-                            tracing::debug!(?response, "Received matching MCP response");
-                            if let Some(error) = response.error {
-                                tracing::error!(?error, "Server returned error");
-                                return Err(Error::Protocol {
-                                    code: error.code.into(),
-                                    message: error.message,
-                                    data: error.data,
-                                });
-                            }
-                            return response.result.ok_or_else(|| {
-                                Error::protocol(ErrorCode::InternalError, "Response missing result")
-                            });
-                        }
-                        Message::Response(response) => {
-                            tracing::debug!(
-                                ?response,
-                                "Received non-matching response, continuing to wait"
-                            );
-                        }
-                        Message::Notification(notif) => {
-                            tracing::debug!(?notif, "Received notification while waiting for response");
-                        }
-                        Message::Request(req) => {
-                            tracing::debug!(?req, "Received request while waiting for response");
-                        }
+            // Branch 1: Send, then wait for the dispatcher to hand us the matching response
+            exchange = exchange => {
+                let response = match exchange {
+                    Ok(response) => response,
+                    Err(e) => {
+                        // The request never reached the server, so there's nothing to cancel.
+                        guard.cancel_reason = None;
+                        return Err(e);
                     }
+                };
+                let response = response.map_err(|_| {
+                    Error::protocol(
+                        ErrorCode::InternalError,
+                        "Connection closed while waiting for response",
+                    )
+                })?;
+                if let Some(error) = response.error {
+                    tracing::error!(?error, "Server returned error");
+                    return Err(Error::Protocol {
+                        code: error.code.into(),
+                        message: error.message,
+                        data: error.data,
+                    });
                 }
+                response.result.ok_or_else(|| {
+                    Error::protocol(ErrorCode::InternalError, "Response missing result")
+                })
+            }
 
-                // Channel closed or no more messages.
-                Err(Error::protocol(
-                    ErrorCode::InternalError,
-                    "Connection closed while waiting for response",
-                ))
-            } => result,
-            
-            // Branch 2: Periodically check if the process is still alive, or timeout after 30s
-            result = async {
-                for _ in 1..=100 {
-                    tokio::time::sleep(Duration::from_millis(300)).await;
-                    
-                    if let Some(process) = &mut self.subprocess {
-                        match process.try_wait() {
-                            Ok(None) => continue,
-                            Ok(Some(exit_status)) => {
-                                return Err(Error::Other(format!("Process exited with status: {}", exit_status)));
-                            },
-                            Err(e) => {
-                                return Err(Error::Other(format!("Error checking process status: {}", e)));
-                            }
-                        }
-                    }
+            // Branch 2: Periodically check if the process is still alive
+            err = self.watch_subprocess() => Err(err),
+
+            // Branch 3: Give up once the timeout elapses
+            _ = tokio::time::sleep(request_timeout) => {
+                let elapsed = started.elapsed();
+                tracing::error!(?elapsed, "Request to '{}' timed out", method);
+                if guard.cancel_reason.is_some() {
+                    guard.cancel_reason = Some(format!("Request timed out after {:?}", elapsed));
                 }
-                
-                tracing::error!("Request to '{}' timed out after 30 seconds", method);
-                Err(Error::Other(format!(
-                    "Request to '{method}' timed out after 30 seconds"
-                )))
-            } => result,
+                Err(Error::Timeout {
+                    method: method.to_string(),
+                    elapsed,
+                })
+            }
+
+            // Branch 4: Stop waiting when the caller cancels the request
+            reason = cancellation.cancelled() => {
+                tracing::debug!(%reason, "Request to '{}' cancelled", method);
+                if guard.cancel_reason.is_some() {
+                    guard.cancel_reason = Some(reason.clone());
+                }
+                Err(Error::Cancelled {
+                    method: method.to_string(),
+                    reason,
+                })
+            }
+        }
+    }
+
+    /// Resolves with an error once the subprocess (if any) has exited.
+    /// Never resolves when the client has no associated subprocess.
+    async fn watch_subprocess(&self) -> Error {
+        loop {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+
+            match self.inner.subprocess.lock().await.as_mut() {
+                Some(process) => match process.try_wait() {
+                    Ok(None) => continue,
+                    Ok(Some(exit_status)) => {
+                        return Error::Other(format!("Process exited with status: {}", exit_status));
+                    }
+                    Err(e) => {
+                        return Error::Other(format!("Error checking process status: {}", e));
+                    }
+                },
+                None => return futures::future::pending().await,
+            }
         }
     }
 
@@ -225,21 +581,97 @@ impl Client {
     ) -> Result<(), Error> {
         let notification = Notification::new(method, params.clone());
         tracing::debug!(?method, ?params, "Sending MCP notification");
-        self.transport
+        self.inner.transport
             .send(Message::Notification(notification))
             .await
     }
 
+    /// Returns a stream of all notifications the server sends from now on.
+    ///
+    /// Each call creates an independent subscriber. A subscriber that falls too far
+    /// behind skips the notifications it missed.
+    pub fn notifications(&self) -> NotificationStream {
+        let rx = self.inner.routes.notifications.subscribe();
+        Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(notification) => return Some((notification, rx)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Notification subscriber lagged behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Registers `handler` to be called for every notification with the given method,
+    /// e.g. `"notifications/tools/list_changed"`.
+    ///
+    /// Handlers run on the client's dispatcher task, so they should return quickly;
+    /// spawn a task for anything slow.
+    pub fn on_notification<F>(&self, method: &str, handler: F)
+    where
+        F: Fn(&ServerNotification) + Send + Sync + 'static,
+    {
+        tracing::debug!(%method, "Registering notification handler");
+        self.inner
+            .routes
+            .handlers
+            .write()
+            .unwrap()
+            .entry(method.to_string())
+            .or_default()
+            .push(Arc::new(handler));
+    }
+
     /// Returns the cached server capabilities if the client has already initialized.
     pub async fn capabilities(&self) -> Option<ServerCapabilities> {
-        let caps = self.server_capabilities.read().await.clone();
+        let caps = self.inner.server_capabilities.read().await.clone();
         tracing::trace!(?caps, "Retrieved server capabilities");
         caps
     }
 
+    /// Returns the protocol version negotiated with the server, if the client has
+    /// already initialized. Versions compare in chronological order, so features can
+    /// be gated with e.g. `version >= ProtocolVersion::V2025_03_26`.
+    pub fn protocol_version(&self) -> Option<ProtocolVersion> {
+        *self.inner.routes.protocol_version.read().unwrap()
+    }
+
+    /// Returns the server's name and version if the client has already initialized.
+    pub fn server_info(&self) -> Option<Implementation> {
+        self.inner.routes.server_info.read().unwrap().clone()
+    }
+
+    /// Sets the minimum level of log messages the server sends, calling `logging/setLevel`.
+    ///
+    /// Received `notifications/message` entries are re-emitted as `tracing` events under
+    /// the [`SERVER_LOG_TARGET`] target, and are also available from `notifications()`.
+    ///
+    /// # Errors
+    ///
+    /// Fails without contacting the server if the initialized server didn't announce
+    /// the `logging` capability.
+    pub async fn set_logging_level(&self, level: LoggingLevel) -> Result<(), Error> {
+        tracing::debug!(%level, "Setting server logging level");
+        if let Some(caps) = self.capabilities().await {
+            if caps.logging.is_none() {
+                return Err(Error::protocol(
+                    ErrorCode::MethodNotFound,
+                    "Server does not support logging",
+                ));
+            }
+        }
+        let params = serde_json::to_value(SetLevelRequest { level })?;
+        self.request("logging/setLevel", Some(params)).await?;
+        Ok(())
+    }
+
     /// Shuts down the client by closing the transport. This does not send a server shutdown request.
-    pub async fn shutdown(&mut self) -> Result<(), Error> {
-        Self::perform_shutdown(self.transport.clone(), &mut self.subprocess).await
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        let mut subprocess = self.inner.subprocess.lock().await;
+        Self::perform_shutdown(self.inner.transport.clone(), &mut subprocess).await
     }
 
     async fn perform_shutdown(
@@ -265,69 +697,350 @@ impl Client {
         Ok(())
     }
 
-    /// Lists available tools on the server by calling `tools/list`.
-    pub async fn list_tools(&mut self) -> Result<ListToolsResult, Error> {
-        tracing::debug!("Listing available tools");
-        let response = self.request("tools/list", None).await?;
+    /// Lists available tools on the server by calling `tools/list`, starting at `cursor`.
+    pub async fn list_tools(&self, cursor: Option<Cursor>) -> Result<ListToolsResult, Error> {
+        tracing::debug!(?cursor, "Listing available tools");
+        let params = serde_json::to_value(ListToolsRequest { cursor })?;
+        let response = self.request("tools/list", Some(params)).await?;
         let result = serde_json::from_value(response).map_err(Error::from);
         tracing::debug!(?result, "Received tools list");
         result
     }
 
+    /// Streams every tool on the server, fetching further `tools/list` pages as needed.
+    pub fn list_tools_paged(&self) -> Paged<Tool> {
+        pagination::paginate(self, "tools/list", |client, cursor| async move {
+            client.list_tools(cursor).await
+        })
+    }
+
+    /// Lists every tool on the server, following pagination.
+    pub async fn list_tools_all(&self) -> Result<Vec<Tool>, Error> {
+        self.list_tools_paged().try_collect().await
+    }
+
+    /// Lists every tool on the server, sending each `tools/list` page with `options`.
+    async fn list_tools_all_with_options(&self, options: RequestOptions) -> Result<Vec<Tool>, Error> {
+        pagination::paginate(self, "tools/list", move |client, cursor| {
+            let options = options.clone();
+            async move {
+                let params = serde_json::to_value(ListToolsRequest { cursor })?;
+                let response = client
+                    .request_with_options("tools/list", Some(params), options)
+                    .await?;
+                Ok(serde_json::from_value::<ListToolsResult>(response)?)
+            }
+        })
+        .try_collect()
+        .await
+    }
+
     /// Calls a tool on the server by name, passing the specified arguments as JSON.
-    /// If the returned `CallToolResult` has `is_error` set to `true`, this method converts
-    /// it into an `Error::Other`.
+    /// If the returned `CallToolResult` has `is_error` set to `true`, this method returns
+    /// it as `Error::ToolExecution`, keeping every content part of the result.
     pub async fn call_tool(
-        &mut self,
+        &self,
         name: &str,
         arguments: serde_json::Value,
+    ) -> Result<CallToolResult, Error> {
+        self.call_tool_with_options(name, arguments, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`Client::call_tool`], but waits at most `timeout` for the tool to finish.
+    pub async fn call_tool_with_timeout(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<CallToolResult, Error> {
+        self.call_tool_with_options(name, arguments, RequestOptions::default().timeout(timeout))
+            .await
+    }
+
+    /// Like [`Client::call_tool`], but asks the server to report progress while the tool runs.
+    ///
+    /// Returns a stream of progress updates together with a future resolving to the final
+    /// result. The stream ends once the call completes; the call only starts once the
+    /// future is polled.
+    pub fn call_tool_with_progress(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> (ProgressStream, BoxFuture<'static, Result<CallToolResult, Error>>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let client = self.clone();
+        let name = name.to_string();
+        let result = Box::pin(async move {
+            client
+                .call_tool_with_options(&name, arguments, RequestOptions::default().progress(tx))
+                .await
+        });
+        let progress = Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|update| (update, rx))
+        }));
+        (progress, result)
+    }
+
+    /// Calls a tool and deserializes its structured result into `T`.
+    ///
+    /// Like `call_tool`, the result is checked against the tool's `outputSchema` if the
+    /// tools are already in the catalog; the tools are never listed just for that.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of `call_tool`, fails with `Error::InvalidToolOutput` if the
+    /// result breaks the tool's output schema, `Error::Other` if it has no
+    /// `structuredContent`, or `Error::Serialization` if it doesn't fit `T`.
+    pub async fn call_tool_typed<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<T, Error> {
+        let result = self.call_tool(name, arguments).await?;
+        let Some(structured) = result.structured_content else {
+            return Err(Error::Other(format!(
+                "Tool '{}' returned no structured content",
+                name
+            )));
+        };
+        Ok(serde_json::from_value(structured)?)
+    }
+
+    /// Calls a tool with per-request options such as a timeout, cancellation or progress listener.
+    ///
+    /// If the tools have already been listed into the catalog and this tool declares an
+    /// `outputSchema`, the structured result is validated against it, failing with
+    /// `Error::InvalidToolOutput` on a mismatch; calling a tool never lists the tools just
+    /// for that. With `ClientConfig::validate_tool_arguments` enabled, the tools are listed
+    /// if needed (under this call's timeout and cancellation) and the arguments are checked
+    /// against the tool's `inputSchema`, failing with `Error::InvalidToolArguments` without
+    /// contacting the server.
+    pub async fn call_tool_with_options(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        options: RequestOptions,
     ) -> Result<CallToolResult, Error> {
         tracing::debug!(%name, ?arguments, "Calling tool");
+        if self.inner.config.validate_tool_arguments {
+            self.check_tool_arguments(name, &arguments, &options).await?;
+        }
+        let output_validator = self.cached_tool_validator(name, ToolSchema::Output);
         let request = CallToolRequest {
             name: name.to_string(),
             arguments,
         };
 
         let response = self
-            .request("tools/call", Some(serde_json::to_value(request)?))
+            .request_with_options("tools/call", Some(serde_json::to_value(request)?), options)
             .await?;
 
         let tool_result: CallToolResult = serde_json::from_value(response)?;
         if tool_result.is_error {
             // We treat tool-level errors (isError=true) as a Rust error.
-            let maybe_msg = tool_result
-                .content
-                .iter()
-                .filter_map(|msg| {
-                    if let crate::types::MessageContent::Text { text } = msg {
-                        Some(text.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
-
-            return Err(Error::Other(format!(
-                "Tool '{}' execution failed: {}",
-                name, maybe_msg
-            )));
+            tracing::debug!(%name, ?tool_result, "Tool reported an execution error");
+            return Err(Error::ToolExecution {
+                tool: name.to_string(),
+                result: Box::new(tool_result),
+            });
+        }
+        if let Some(validator) = output_validator {
+            Self::check_tool_output(name, &validator, &tool_result)?;
         }
         tracing::debug!(?tool_result, "Tool call succeeded");
         Ok(tool_result)
     }
 
-    /// Retrieves a single tool from the server by name, returning `Some(tool)` if found, or `None` otherwise.
-    pub async fn get_tool(&mut self, name: &str) -> Result<Option<Tool>, Error> {
+    /// Checks tool arguments against the tool's input schema, listing the tools with the
+    /// call's timeout and cancellation if needed. Tools the server doesn't list are left
+    /// for the server to reject.
+    async fn check_tool_arguments(
+        &self,
+        name: &str,
+        arguments: &serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<(), Error> {
+        let listing = RequestOptions {
+            timeout: options.timeout,
+            cancellation: options.cancellation.clone(),
+            progress: None,
+        };
+        let tools = self
+            .cached(
+                CatalogKind::Tools,
+                |catalog| catalog.tools.clone(),
+                |catalog, tools| catalog.tools = Some(tools),
+                self.list_tools_all_with_options(listing),
+            )
+            .await?;
+        let Some(validator) = self.tool_validator(name, ToolSchema::Input, &tools) else {
+            tracing::debug!(%name, "Not validating arguments of unlisted tool");
+            return Ok(());
+        };
+        validation::validate(&validator, arguments).map_err(|violations| {
+            tracing::debug!(%name, ?violations, "Tool arguments do not match inputSchema");
+            Error::InvalidToolArguments {
+                tool: name.to_string(),
+                violations,
+            }
+        })
+    }
+
+    /// Checks a successful tool result against the tool's compiled output schema.
+    fn check_tool_output(
+        name: &str,
+        validator: &jsonschema::Validator,
+        result: &CallToolResult,
+    ) -> Result<(), Error> {
+        let violations = match &result.structured_content {
+            Some(structured) => match validation::validate(validator, structured) {
+                Ok(()) => return Ok(()),
+                Err(violations) => violations,
+            },
+            None => vec![SchemaViolation {
+                path: String::new(),
+                message: "structuredContent is missing".to_string(),
+            }],
+        };
+        tracing::warn!(%name, ?violations, "Tool output does not match its outputSchema");
+        Err(Error::InvalidToolOutput {
+            tool: name.to_string(),
+            violations,
+        })
+    }
+
+    /// Returns the compiled `schema` of tool `name` if the tools are already in the
+    /// catalog, without listing them.
+    fn cached_tool_validator(
+        &self,
+        name: &str,
+        schema: ToolSchema,
+    ) -> Option<Arc<jsonschema::Validator>> {
+        let tools = self.inner.routes.catalog.borrow().tools.clone();
+        let Some(tools) = tools else {
+            tracing::debug!(%name, "Tools not listed yet, not validating the result");
+            return None;
+        };
+        self.tool_validator(name, schema, &tools)
+    }
+
+    /// Returns the compiled `schema` of tool `name` from `tools`. The schema is compiled
+    /// once and kept in the catalog until the tools are next listed. `None` if the tool
+    /// isn't listed, doesn't declare the schema, or it doesn't compile.
+    fn tool_validator(
+        &self,
+        name: &str,
+        schema: ToolSchema,
+        tools: &[Tool],
+    ) -> Option<Arc<jsonschema::Validator>> {
+        let catalog = &self.inner.routes.catalog;
+        let generation = {
+            let catalog = catalog.borrow();
+            if let Some(validator) = catalog.validator(name, schema) {
+                return validator;
+            }
+            catalog.generation(CatalogKind::Tools)
+        };
+
+        let tool = tools.iter().find(|tool| tool.name == name)?;
+        let declared = match schema {
+            ToolSchema::Input => Some(&tool.input_schema),
+            ToolSchema::Output => tool.output_schema.as_ref(),
+        };
+        let validator = declared.and_then(validation::compile).map(Arc::new);
+        catalog.send_if_modified(|catalog| {
+            // Don't keep a schema of tools listed before the latest change.
+            if catalog.generation(CatalogKind::Tools) == generation {
+                catalog.set_validator(name, schema, validator.clone());
+            }
+            false
+        });
+        validator
+    }
+
+    /// Retrieves a single tool by name from the cached catalog (listing the tools first
+    /// if needed), returning `Some(tool)` if found, or `None` otherwise.
+    pub async fn get_tool(&self, name: &str) -> Result<Option<Tool>, Error> {
         tracing::debug!(%name, "Getting specific tool");
-        let tools = self.list_tools().await?;
-        let tool = tools.tools.into_iter().find(|t| t.name == name);
+        let tool = self.tools().await?.into_iter().find(|t| t.name == name);
         tracing::debug!(?tool, "Found tool");
         Ok(tool)
     }
 
+    /// Returns every tool on the server from the catalog, listing them on first use
+    /// or after the server reported a change.
+    pub async fn tools(&self) -> Result<Vec<Tool>, Error> {
+        self.cached(
+            CatalogKind::Tools,
+            |catalog| catalog.tools.clone(),
+            |catalog, tools| catalog.tools = Some(tools),
+            self.list_tools_all(),
+        )
+        .await
+    }
+
+    /// Returns every prompt on the server from the catalog, listing them on first use
+    /// or after the server reported a change.
+    pub async fn prompts(&self) -> Result<Vec<Prompt>, Error> {
+        self.cached(
+            CatalogKind::Prompts,
+            |catalog| catalog.prompts.clone(),
+            |catalog, prompts| catalog.prompts = Some(prompts),
+            self.list_prompts_all(),
+        )
+        .await
+    }
+
+    /// Returns every resource on the server from the catalog, listing them on first use
+    /// or after the server reported a change.
+    pub async fn resources(&self) -> Result<Vec<Resource>, Error> {
+        self.cached(
+            CatalogKind::Resources,
+            |catalog| catalog.resources.clone(),
+            |catalog, resources| catalog.resources = Some(resources),
+            self.list_resources_all(),
+        )
+        .await
+    }
+
+    /// Watches the catalog of tools, prompts and resources. The receiver is notified
+    /// whenever a list is loaded, or dropped after a `list_changed` notification.
+    pub fn catalog(&self) -> watch::Receiver<Catalog> {
+        self.inner.routes.catalog.subscribe()
+    }
+
+    /// Returns a catalog list, or runs `fetch` and stores its result unless the list
+    /// was invalidated in the meantime.
+    async fn cached<T: Clone>(
+        &self,
+        kind: CatalogKind,
+        get: impl Fn(&Catalog) -> Option<Vec<T>>,
+        set: impl FnOnce(&mut Catalog, Vec<T>),
+        fetch: impl std::future::Future<Output = Result<Vec<T>, Error>>,
+    ) -> Result<Vec<T>, Error> {
+        let generation = {
+            let catalog = self.inner.routes.catalog.borrow();
+            if let Some(items) = get(&catalog) {
+                return Ok(items);
+            }
+            catalog.generation(kind)
+        };
+
+        let items = fetch.await?;
+        self.inner.routes.catalog.send_if_modified(|catalog| {
+            if catalog.generation(kind) != generation {
+                tracing::debug!(?kind, "Not caching listing invalidated while in flight");
+                return false;
+            }
+            set(catalog, items.clone());
+            true
+        });
+        Ok(items)
+    }
+
     /// Reads a resource by URI from the server, calling `resources/read`.
-    pub async fn read_resource(&mut self, uri: &str) -> Result<ReadResourceResult, Error> {
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error> {
         tracing::debug!(%uri, "Reading resource");
         let params = serde_json::json!({ "uri": uri });
         let response = self.request("resources/read", Some(params)).await?;
@@ -336,18 +1049,230 @@ impl Client {
         result
     }
 
-    /// Lists resources by calling `resources/list` on the server.
-    pub async fn list_resources(&mut self) -> Result<ListResourcesResult, Error> {
-        tracing::debug!("Listing available resources");
-        let response = self.request("resources/list", None).await?;
+    /// Subscribes to changes of the resource at `uri` by calling `resources/subscribe`.
+    ///
+    /// The returned subscription yields an event for every `notifications/resources/updated`
+    /// about this URI; call `read_updates` on it to receive fresh contents instead. Several
+    /// subscriptions to the same URI may be live at once; dropping the last of them sends
+    /// `resources/unsubscribe`.
+    ///
+    /// # Errors
+    ///
+    /// Fails without contacting the server if the initialized server didn't announce
+    /// `resources.subscribe` support.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<ResourceSubscription, Error> {
+        tracing::debug!(%uri, "Subscribing to resource");
+        if let Some(caps) = self.capabilities().await {
+            let supported = caps
+                .resources
+                .and_then(|resources| resources.subscribe)
+                .unwrap_or(false);
+            if !supported {
+                return Err(Error::protocol(
+                    ErrorCode::MethodNotFound,
+                    "Server does not support resource subscriptions",
+                ));
+            }
+        }
+
+        // Listen before subscribing so an update sent right away isn't missed.
+        let notifications = self.notifications();
+        let params = serde_json::json!({ "uri": uri });
+        // Count the subscription before sending so a concurrent drop of another
+        // subscription to this URI doesn't unsubscribe underneath it.
+        *self
+            .inner
+            .routes
+            .subscriptions
+            .lock()
+            .unwrap()
+            .entry(uri.to_string())
+            .or_default() += 1;
+        if let Err(e) = self.request("resources/subscribe", Some(params)).await {
+            self.release_subscription(uri);
+            return Err(e);
+        }
+        Ok(ResourceSubscription::new(
+            uri.to_string(),
+            self.clone(),
+            notifications,
+        ))
+    }
+
+    /// Forgets one live subscription to `uri`, returning whether it was the last one.
+    pub(crate) fn release_subscription(&self, uri: &str) -> bool {
+        let mut subscriptions = self.inner.routes.subscriptions.lock().unwrap();
+        match subscriptions.get_mut(uri) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => {
+                subscriptions.remove(uri);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Lists resources by calling `resources/list` on the server, starting at `cursor`.
+    pub async fn list_resources(
+        &self,
+        cursor: Option<Cursor>,
+    ) -> Result<ListResourcesResult, Error> {
+        tracing::debug!(?cursor, "Listing available resources");
+        let params = serde_json::to_value(ListResourcesRequest {
+            root_id: None,
+            cursor,
+        })?;
+        let response = self.request("resources/list", Some(params)).await?;
         let result = serde_json::from_value(response).map_err(Error::from);
         tracing::debug!(?result, "Received resources list");
         result
     }
 
+    /// Streams every resource on the server, fetching further `resources/list` pages as needed.
+    pub fn list_resources_paged(&self) -> Paged<Resource> {
+        pagination::paginate(self, "resources/list", |client, cursor| async move {
+            client.list_resources(cursor).await
+        })
+    }
+
+    /// Lists every resource on the server, following pagination.
+    pub async fn list_resources_all(&self) -> Result<Vec<Resource>, Error> {
+        self.list_resources_paged().try_collect().await
+    }
+
+    /// Lists resource templates by calling `resources/templates/list` on the server,
+    /// starting at `cursor`. Use `ResourceTemplate::parse_template` to expand them into URIs.
+    pub async fn list_resource_templates(
+        &self,
+        cursor: Option<Cursor>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        tracing::debug!(?cursor, "Listing available resource templates");
+        let params = serde_json::to_value(ListResourceTemplatesRequest { cursor })?;
+        let response = self.request("resources/templates/list", Some(params)).await?;
+        let result = serde_json::from_value(response).map_err(Error::from);
+        tracing::debug!(?result, "Received resource templates list");
+        result
+    }
+
+    /// Streams every resource template on the server, fetching further
+    /// `resources/templates/list` pages as needed.
+    pub fn list_resource_templates_paged(&self) -> Paged<ResourceTemplate> {
+        pagination::paginate(self, "resources/templates/list", |client, cursor| async move {
+            client.list_resource_templates(cursor).await
+        })
+    }
+
+    /// Lists every resource template on the server, following pagination.
+    pub async fn list_resource_templates_all(&self) -> Result<Vec<ResourceTemplate>, Error> {
+        self.list_resource_templates_paged().try_collect().await
+    }
+
+    /// Lists prompts by calling `prompts/list` on the server, starting at `cursor`.
+    pub async fn list_prompts(&self, cursor: Option<Cursor>) -> Result<ListPromptsResult, Error> {
+        tracing::debug!(?cursor, "Listing available prompts");
+        let params = serde_json::to_value(ListPromptsRequest { cursor })?;
+        let response = self.request("prompts/list", Some(params)).await?;
+        let result = serde_json::from_value(response).map_err(Error::from);
+        tracing::debug!(?result, "Received prompts list");
+        result
+    }
+
+    /// Streams every prompt on the server, fetching further `prompts/list` pages as needed.
+    pub fn list_prompts_paged(&self) -> Paged<Prompt> {
+        pagination::paginate(self, "prompts/list", |client, cursor| async move {
+            client.list_prompts(cursor).await
+        })
+    }
+
+    /// Lists every prompt on the server, following pagination.
+    pub async fn list_prompts_all(&self) -> Result<Vec<Prompt>, Error> {
+        self.list_prompts_paged().try_collect().await
+    }
+
+    /// Retrieves a single prompt definition by name from the cached catalog (listing
+    /// the prompts first if needed), returning `Some(prompt)` if found, or `None` otherwise.
+    pub async fn find_prompt(&self, name: &str) -> Result<Option<Prompt>, Error> {
+        Ok(self.prompts().await?.into_iter().find(|p| p.name == name))
+    }
+
+    /// Gets a prompt from the server by name, filling in its template with `arguments`,
+    /// by calling `prompts/get`.
+    ///
+    /// If the prompts are already in the catalog and list this one, the arguments are
+    /// first checked against its required arguments, failing with
+    /// `ErrorCode::InvalidParams` if any are missing. Getting a prompt never lists the
+    /// prompts just for that.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<GetPromptResult, Error> {
+        tracing::debug!(%name, ?arguments, "Getting prompt");
+        let prompts = self.inner.routes.catalog.borrow().prompts.clone();
+        let prompt = prompts.and_then(|prompts| prompts.into_iter().find(|p| p.name == name));
+        if let Some(prompt) = prompt {
+            let missing: Vec<&str> = prompt
+                .required_arguments()
+                .filter(|arg| !arguments.contains_key(*arg))
+                .collect();
+            if !missing.is_empty() {
+                return Err(Error::protocol(
+                    ErrorCode::InvalidParams,
+                    format!(
+                        "Missing required arguments for prompt '{}': {}",
+                        name,
+                        missing.join(", ")
+                    ),
+                )
+                .with_data(serde_json::json!({ "missing": missing })));
+            }
+        }
+
+        let request = GetPromptRequest {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        let response = self
+            .request("prompts/get", Some(serde_json::to_value(request)?))
+            .await?;
+        let result = serde_json::from_value(response).map_err(Error::from);
+        tracing::debug!(?result, "Received prompt");
+        result
+    }
+
+    /// Asks the server for completions of a prompt or resource template argument,
+    /// calling `completion/complete`.
+    ///
+    /// `reference` names the prompt or template, `argument_name` the argument being
+    /// completed and `partial_value` what the user has typed so far.
+    pub async fn complete(
+        &self,
+        reference: CompletionReference,
+        argument_name: &str,
+        partial_value: &str,
+    ) -> Result<CompleteResult, Error> {
+        tracing::debug!(?reference, %argument_name, %partial_value, "Requesting completion");
+        let request = CompleteRequest {
+            reference,
+            argument: CompletionArgument {
+                name: argument_name.to_string(),
+                value: partial_value.to_string(),
+            },
+        };
+        let response = self
+            .request("completion/complete", Some(serde_json::to_value(request)?))
+            .await?;
+        let result = serde_json::from_value(response).map_err(Error::from);
+        tracing::debug!(?result, "Received completion");
+        result
+    }
+
     /// Reads last `tail_lines` lines from stderr file (100 by default).
     pub async fn get_stderr(&self, tail_lines: Option<usize>) -> Result<String, Error> {
-        if let Some(file) = &self.stderr_file {
+        if let Some(file) = &self.inner.stderr_file {
             let path = file.path();
             let line_count = tail_lines.unwrap_or(100);
             
@@ -371,10 +1296,11 @@ impl Client {
     }
 }
 
+// Runs once the last `Client` clone is gone.
 // Like calling `shutdown` explicitly, but not waiting for it to complete.
-impl Drop for Client {
+impl Drop for ClientInner {
     fn drop(&mut self) {
-        let mut subprocess = self.subprocess.take();
+        let mut subprocess = self.subprocess.get_mut().take();
         let transport = self.transport.clone();
         
         tokio::spawn(async move {
@@ -383,4 +1309,4 @@ impl Drop for Client {
             }
         });
     }
-}
\ No newline at end of file
+}
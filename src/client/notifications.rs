@@ -0,0 +1,68 @@
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+use crate::{
+    protocol::Notification,
+    types::{
+        CancelledNotification, LoggingMessageNotification, ProgressNotification,
+        ResourceUpdatedNotification,
+    },
+};
+
+/// A callback registered with `Client::on_notification`.
+pub type NotificationHandler = Arc<dyn Fn(&ServerNotification) + Send + Sync>;
+
+/// A notification sent by the server, parsed into a typed form where the method is known.
+#[derive(Debug, Clone)]
+pub enum ServerNotification {
+    /// `notifications/cancelled`: the server gave up on a request it sent us.
+    Cancelled(CancelledNotification),
+    /// `notifications/progress`: progress for a request we sent.
+    Progress(ProgressNotification),
+    /// `notifications/message`: a log entry from the server.
+    LoggingMessage(LoggingMessageNotification),
+    /// `notifications/resources/updated`: a subscribed resource changed.
+    ResourceUpdated(ResourceUpdatedNotification),
+    /// `notifications/resources/list_changed`
+    ResourceListChanged,
+    /// `notifications/tools/list_changed`
+    ToolListChanged,
+    /// `notifications/prompts/list_changed`
+    PromptListChanged,
+    /// Any other notification, or a known one whose params didn't parse.
+    Other(Notification),
+}
+
+impl ServerNotification {
+    /// Parses `params` as `T`, falling back to `Other` if they don't match.
+    fn parse<T: DeserializeOwned>(
+        notification: Notification,
+        wrap: impl FnOnce(T) -> ServerNotification,
+    ) -> ServerNotification {
+        let params = notification.params.clone().unwrap_or_default();
+        match serde_json::from_value(params) {
+            Ok(params) => wrap(params),
+            Err(e) => {
+                tracing::debug!(?e, method = %notification.method, "Failed to parse notification params");
+                ServerNotification::Other(notification)
+            }
+        }
+    }
+}
+
+impl From<Notification> for ServerNotification {
+    fn from(notification: Notification) -> Self {
+        match notification.method.as_str() {
+            "notifications/cancelled" => Self::parse(notification, ServerNotification::Cancelled),
+            "notifications/progress" => Self::parse(notification, ServerNotification::Progress),
+            "notifications/message" => Self::parse(notification, ServerNotification::LoggingMessage),
+            "notifications/resources/updated" => {
+                Self::parse(notification, ServerNotification::ResourceUpdated)
+            }
+            "notifications/resources/list_changed" => ServerNotification::ResourceListChanged,
+            "notifications/tools/list_changed" => ServerNotification::ToolListChanged,
+            "notifications/prompts/list_changed" => ServerNotification::PromptListChanged,
+            _ => ServerNotification::Other(notification),
+        }
+    }
+}
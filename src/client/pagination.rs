@@ -0,0 +1,88 @@
+use futures::Stream;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::Client;
+use crate::{
+    error::Error,
+    types::{
+        Cursor, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, Prompt, Resource, ResourceTemplate, Tool,
+    },
+};
+
+/// A stream of listed items, fetching further pages as it is polled.
+pub type Paged<T> = Pin<Box<dyn Stream<Item = Result<T, Error>> + Send>>;
+
+/// One page of a paginated list result.
+pub(crate) trait Page {
+    type Item;
+
+    /// Splits the page into its items and the cursor of the next page, if any.
+    fn into_parts(self) -> (Vec<Self::Item>, Option<Cursor>);
+}
+
+impl Page for ListToolsResult {
+    type Item = Tool;
+
+    fn into_parts(self) -> (Vec<Tool>, Option<Cursor>) {
+        (self.tools, self.next_cursor)
+    }
+}
+
+impl Page for ListResourcesResult {
+    type Item = Resource;
+
+    fn into_parts(self) -> (Vec<Resource>, Option<Cursor>) {
+        (self.resources, self.next_cursor)
+    }
+}
+
+impl Page for ListResourceTemplatesResult {
+    type Item = ResourceTemplate;
+
+    fn into_parts(self) -> (Vec<ResourceTemplate>, Option<Cursor>) {
+        (self.resource_templates, self.next_cursor)
+    }
+}
+
+impl Page for ListPromptsResult {
+    type Item = Prompt;
+
+    fn into_parts(self) -> (Vec<Prompt>, Option<Cursor>) {
+        (self.prompts, self.next_cursor)
+    }
+}
+
+/// Streams the items of every page `fetch` returns, following `nextCursor` until the
+/// server stops sending one, or failing with `Error::TooManyPages` after `max_pages`.
+pub(crate) fn paginate<P, F, Fut>(client: &Client, method: &'static str, fetch: F) -> Paged<P::Item>
+where
+    P: Page + Send,
+    P::Item: Send + 'static,
+    F: Fn(Client, Option<Cursor>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<P, Error>> + Send,
+{
+    let client = client.clone();
+    let limit = client.inner.config.max_pages;
+    Box::pin(async_stream::try_stream! {
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            if pages == limit {
+                tracing::warn!(%method, limit, "Server kept paginating past the page limit");
+                Err(Error::TooManyPages { method: method.to_string(), limit })?;
+            }
+            pages += 1;
+
+            let (items, next_cursor) = fetch(client.clone(), cursor.take()).await?.into_parts();
+            for item in items {
+                yield item;
+            }
+            match next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+    })
+}
@@ -0,0 +1,88 @@
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::{Client, NotificationStream, ServerNotification};
+use crate::{
+    error::Error,
+    types::{ReadResourceResult, ResourceUpdatedNotification},
+};
+
+/// A live `resources/subscribe` subscription, created by `Client::subscribe_resource`.
+///
+/// Yields a `notifications/resources/updated` event each time the server reports a
+/// change to the subscribed URI. Dropping the last subscription to a URI sends
+/// `resources/unsubscribe`.
+pub struct ResourceSubscription {
+    uri: String,
+    client: Client,
+    notifications: NotificationStream,
+}
+
+impl ResourceSubscription {
+    pub(crate) fn new(uri: String, client: Client, notifications: NotificationStream) -> Self {
+        Self {
+            uri,
+            client,
+            notifications,
+        }
+    }
+
+    /// The URI this subscription watches.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Turns the subscription into a stream that re-reads the resource on every update,
+    /// yielding its fresh contents. The subscription ends when the stream is dropped.
+    pub fn read_updates(
+        self,
+    ) -> Pin<Box<dyn Stream<Item = Result<ReadResourceResult, Error>> + Send>> {
+        let client = self.client.clone();
+        Box::pin(self.then(move |update| {
+            let client = client.clone();
+            async move { client.read_resource(&update.uri).await }
+        }))
+    }
+}
+
+impl Stream for ResourceSubscription {
+    type Item = ResourceUpdatedNotification;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.notifications.poll_next_unpin(cx) {
+                Poll::Ready(Some(ServerNotification::ResourceUpdated(update)))
+                    if update.uri == self.uri =>
+                {
+                    return Poll::Ready(Some(update));
+                }
+                Poll::Ready(Some(_)) => continue,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl Drop for ResourceSubscription {
+    fn drop(&mut self) {
+        if !self.client.release_subscription(&self.uri) {
+            tracing::debug!(uri = %self.uri, "Other subscriptions still watch this resource");
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!(uri = %self.uri, "No runtime available to unsubscribe");
+            return;
+        };
+        let client = self.client.clone();
+        let uri = std::mem::take(&mut self.uri);
+        runtime.spawn(async move {
+            tracing::debug!(%uri, "Unsubscribing from resource");
+            let params = serde_json::json!({ "uri": uri });
+            if let Err(e) = client.request("resources/unsubscribe", Some(params)).await {
+                tracing::warn!(?e, %uri, "Failed to unsubscribe from resource");
+            }
+        });
+    }
+}
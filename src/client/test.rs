@@ -1,10 +1,174 @@
 use crate::client::builder::ClientBuilder;
 use crate::error::Error;
-use crate::types::{
-    CallToolResult, ClientCapabilities, ListToolsResult, MessageContent, ReadResourceResult,
-    ServerCapabilities, Tool,
-};
+use crate::protocol::{Notification, Response};
+use crate::transport::{Message, Transport};
+use crate::types::{MessageContent, ServerCapabilities};
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio;
+use tokio::sync::broadcast;
+
+/// Initializes `client` as a client named "test" with default capabilities.
+pub(crate) async fn initialize(
+    client: &crate::client::Client,
+) -> Result<crate::types::InitializeResult, Error> {
+    let implementation = crate::types::Implementation {
+        name: "test".to_string(),
+        title: None,
+        version: "0.1.0".to_string(),
+    };
+    client
+        .initialize(implementation, crate::types::ClientCapabilities::default())
+        .await
+}
+
+/// An in-process transport that answers every request after `delayMs`
+/// milliseconds, echoing the params back as the result (wrapped in text content
+/// for `tools/call`). Options are read from `params` or, for tool calls, from
+/// `params.arguments`. With `progressSteps: n`, it first reports `n` progress
+/// updates. Methods given a canned result via `reply_with` answer with it instead;
+/// those given pages via `reply_paged` answer with the page their cursor names.
+/// Requests, notifications and responses sent by the client are recorded.
+struct EchoTransport {
+    sender: broadcast::Sender<Result<Message, Error>>,
+    canned: Arc<std::sync::Mutex<std::collections::HashMap<String, serde_json::Value>>>,
+    paged: Arc<std::sync::Mutex<std::collections::HashMap<String, Vec<serde_json::Value>>>>,
+    requests: Arc<std::sync::Mutex<Vec<crate::protocol::Request>>>,
+    notifications: Arc<std::sync::Mutex<Vec<Notification>>>,
+    responses: Arc<std::sync::Mutex<Vec<Response>>>,
+}
+
+impl EchoTransport {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(100);
+        Self {
+            sender,
+            canned: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            paged: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            requests: Arc::new(std::sync::Mutex::new(Vec::new())),
+            notifications: Arc::new(std::sync::Mutex::new(Vec::new())),
+            responses: Arc::new(std::sync::Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Answers every future `method` request with `result`.
+    fn reply_with(self, method: &str, result: serde_json::Value) -> Self {
+        self.canned
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), result);
+        self
+    }
+
+    /// Answers `method` with `pages[n]` for cursor `"n"` (page 0 without a cursor),
+    /// adding a `nextCursor` to every page but the last.
+    fn reply_paged(self, method: &str, pages: Vec<serde_json::Value>) -> Self {
+        let count = pages.len();
+        let pages = pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut page)| {
+                if i + 1 < count {
+                    page["nextCursor"] = serde_json::json!((i + 1).to_string());
+                }
+                page
+            })
+            .collect();
+        self.paged.lock().unwrap().insert(method.to_string(), pages);
+        self
+    }
+}
+
+#[async_trait]
+impl Transport for EchoTransport {
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        if let Message::Notification(notification) = &message {
+            self.notifications.lock().unwrap().push(notification.clone());
+        }
+        if let Message::Response(response) = &message {
+            self.responses.lock().unwrap().push(response.clone());
+        }
+        if let Message::Request(request) = message {
+            self.requests.lock().unwrap().push(request.clone());
+            let params = request.params.clone().unwrap_or_default();
+            let option = |key: &str| {
+                params
+                    .get(key)
+                    .or_else(|| params.get("arguments").and_then(|a| a.get(key)))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0)
+            };
+            let delay = option("delayMs");
+            let steps = option("progressSteps");
+            let token = params["_meta"]["progressToken"].clone();
+            let page = params["cursor"].as_str().map_or(0, |c| c.parse().unwrap());
+            let canned = self.canned.lock().unwrap().get(&request.method).cloned().or_else(|| {
+                let paged = self.paged.lock().unwrap();
+                paged.get(&request.method).map(|pages| pages[page].clone())
+            });
+            let result = if let Some(result) = canned {
+                result
+            } else if request.method == "tools/call" {
+                serde_json::json!({
+                    "content": [{ "type": "text", "text": params["arguments"].to_string() }]
+                })
+            } else {
+                params.clone()
+            };
+
+            let sender = self.sender.clone();
+            tokio::spawn(async move {
+                for step in 1..=steps {
+                    let progress = Notification::new(
+                        "notifications/progress",
+                        Some(serde_json::json!({
+                            "progressToken": token,
+                            "progress": step,
+                            "total": steps,
+                        })),
+                    );
+                    let _ = sender.send(Ok(Message::Notification(progress)));
+                }
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                let response = Response::success(request.id, Some(result));
+                let _ = sender.send(Ok(Message::Response(response)));
+            });
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        let rx = self.sender.subscribe();
+        Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            match rx.recv().await {
+                Ok(msg) => Some((msg, rx)),
+                Err(_) => None,
+            }
+        }))
+    }
+
+    async fn close(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Tests that concurrent requests each receive their own response, even when
+/// the server answers them out of order.
+#[tokio::test]
+async fn test_concurrent_requests_out_of_order() -> Result<(), Error> {
+    let client = crate::client::Client::new(Arc::new(EchoTransport::new()), None, None);
+
+    let slow = client.request("slow", Some(serde_json::json!({ "delayMs": 300, "tag": "slow" })));
+    let fast = client.request("fast", Some(serde_json::json!({ "delayMs": 10, "tag": "fast" })));
+    let (slow, fast) = tokio::join!(slow, fast);
+
+    assert_eq!(slow?["tag"], "slow");
+    assert_eq!(fast?["tag"], "fast");
+    Ok(())
+}
 
 /// Creates a test client by spawning the `uvx` process with the `notes-simple` argument.
 async fn create_test_client() -> Result<crate::client::Client, Error> {
@@ -30,7 +194,7 @@ async fn test_notes_simple_basic_functionality() -> Result<(), Error> {
 #[tokio::test]
 async fn test_list_tools_schema() -> Result<(), Error> {
     let client = create_test_client().await?;
-    let tools_result = client.list_tools().await?;
+    let tools_result = client.list_tools(None).await?;
     assert!(
         !tools_result.tools.is_empty(),
         "Expected at least one tool from the server"
@@ -86,13 +250,13 @@ async fn test_call_add_note_success() -> Result<(), Error> {
     });
 
     let call_result = client.call_tool("add-note", arguments).await?;
-    assert_eq!(call_result.is_error, false, "Tool call should succeed");
+    assert!(!call_result.is_error, "Tool call should succeed");
     assert!(
         !call_result.content.is_empty(),
         "Expected some text content after calling add-note"
     );
 
-    if let Some(MessageContent::Text { text }) = call_result.content.first() {
+    if let Some(MessageContent::Text { text, .. }) = call_result.content.first() {
         assert!(
             text.contains("my-test-note"),
             "Response text should mention the newly added note name"
@@ -223,7 +387,7 @@ async fn test_resource_list_changed_notification_handling() -> Result<(), Error>
     let _ = client.call_tool("add-note", arguments).await?;
 
     // Confirm the client still works after receiving notifications
-    let tools_result = client.list_tools().await?;
+    let tools_result = client.list_tools(None).await?;
     assert!(
         !tools_result.tools.is_empty(),
         "Expected the client to still be able to list tools"
@@ -242,7 +406,7 @@ async fn test_ping_request() -> Result<(), Error> {
         Ok(val) => {
             // If we got a "result": {} => that's a success
             assert!(
-                val.as_object().map_or(true, |map| map.is_empty()),
+                val.as_object().is_none_or(|map| map.is_empty()),
                 "Expected an empty result object for ping"
             );
         }
@@ -260,10 +424,7 @@ async fn test_set_log_level() -> Result<(), Error> {
     let client = create_test_client().await?;
     // The server might not implement logging. Let's just attempt "logging/setLevel".
     let set_result = client
-        .request(
-            "logging/setLevel",
-            Some(serde_json::json!({ "level": "info" })),
-        )
+        .set_logging_level(crate::types::LoggingLevel::Info)
         .await;
     match set_result {
         Ok(_) => {
@@ -277,3 +438,1096 @@ async fn test_set_log_level() -> Result<(), Error> {
     }
     Ok(())
 }
+
+/// Tests that clones of a client can issue requests independently from separate tasks.
+#[tokio::test]
+async fn test_cloned_client_across_tasks() -> Result<(), Error> {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<crate::client::Client>();
+
+    let client = crate::client::Client::new(Arc::new(EchoTransport::new()), None, None);
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                client
+                    .request("echo", Some(serde_json::json!({ "delayMs": 50 - i * 5, "n": i })))
+                    .await
+            })
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let result = handle.await.expect("task panicked")?;
+        assert_eq!(result["n"], i);
+    }
+    Ok(())
+}
+
+/// Tests that a per-call timeout fails fast with `Error::Timeout`.
+#[tokio::test]
+async fn test_request_timeout() {
+    let client = crate::client::Client::new(Arc::new(EchoTransport::new()), None, None);
+
+    let options = crate::client::RequestOptions::default().timeout(Duration::from_millis(50));
+    let result = client
+        .request_with_options("slow", Some(serde_json::json!({ "delayMs": 1000 })), options)
+        .await;
+
+    match result {
+        Err(Error::Timeout { method, elapsed }) => {
+            assert_eq!(method, "slow");
+            assert!(elapsed >= Duration::from_millis(50));
+        }
+        other => panic!("Expected timeout error, got {:?}", other),
+    }
+}
+
+/// Tests that per-method timeouts from the client configuration are honored.
+#[tokio::test]
+async fn test_method_timeout_override() {
+    let mut config = crate::client::ClientConfig::default();
+    config
+        .method_timeouts
+        .insert("tools/list".to_string(), Duration::from_millis(50));
+    let client = crate::client::Client::with_config(Arc::new(EchoTransport::new()), None, None, config);
+
+    let quick = client
+        .request("tools/list", Some(serde_json::json!({ "delayMs": 500 })))
+        .await;
+    assert!(matches!(quick, Err(Error::Timeout { .. })));
+
+    let other = client
+        .request("other", Some(serde_json::json!({ "delayMs": 100 })))
+        .await;
+    assert!(other.is_ok(), "Methods without an override use the default timeout");
+}
+
+/// Tests that cancelling a request through its token fails the call and notifies the server.
+#[tokio::test]
+async fn test_explicit_cancellation_notifies_server() {
+    let transport = EchoTransport::new();
+    let notifications = transport.notifications.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let token = crate::client::CancellationToken::new();
+    let options = crate::client::RequestOptions::default().cancellation(token.clone());
+    let canceller = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel("user pressed stop");
+    });
+
+    let result = client
+        .request_with_options("slow", Some(serde_json::json!({ "delayMs": 1000 })), options)
+        .await;
+    canceller.await.unwrap();
+    assert!(matches!(result, Err(Error::Cancelled { ref reason, .. }) if reason == "user pressed stop"));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let notifications = notifications.lock().unwrap();
+    let cancelled = notifications
+        .iter()
+        .find(|n| n.method == "notifications/cancelled")
+        .expect("Expected a notifications/cancelled message");
+    let params = cancelled.params.as_ref().unwrap();
+    assert_eq!(params["requestId"], 1);
+    assert_eq!(params["reason"], "user pressed stop");
+}
+
+/// Tests that dropping a pending request future sends `notifications/cancelled`,
+/// while completed requests do not.
+#[tokio::test]
+async fn test_dropped_request_notifies_server() {
+    let transport = EchoTransport::new();
+    let notifications = transport.notifications.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    client
+        .request("fast", Some(serde_json::json!({ "delayMs": 0 })))
+        .await
+        .unwrap();
+
+    let dropped = tokio::time::timeout(
+        Duration::from_millis(50),
+        client.request("slow", Some(serde_json::json!({ "delayMs": 1000 }))),
+    )
+    .await;
+    assert!(dropped.is_err(), "Outer timeout should drop the request future");
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let notifications = notifications.lock().unwrap();
+    let cancelled: Vec<_> = notifications
+        .iter()
+        .filter(|n| n.method == "notifications/cancelled")
+        .collect();
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].params.as_ref().unwrap()["requestId"], 2);
+}
+
+/// Tests that `call_tool_with_progress` attaches a progress token and streams updates.
+#[tokio::test]
+async fn test_call_tool_with_progress() -> Result<(), Error> {
+    use futures::StreamExt;
+
+    let client = crate::client::Client::new(Arc::new(EchoTransport::new()), None, None);
+
+    let (progress, result) =
+        client.call_tool_with_progress("long-task", serde_json::json!({ "progressSteps": 3 }));
+    let result = result.await?;
+    let updates: Vec<_> = progress.collect().await;
+
+    assert!(!result.is_error);
+    assert_eq!(updates.len(), 3);
+    assert_eq!(updates[2].progress, 3.0);
+    assert_eq!(updates[2].total, Some(3.0));
+    Ok(())
+}
+
+/// Tests that server notifications reach `notifications()` subscribers in typed form,
+/// even when no request is pending.
+#[tokio::test]
+async fn test_notification_stream() {
+    use crate::client::ServerNotification;
+    use futures::StreamExt;
+
+    let transport = EchoTransport::new();
+    let server = transport.sender.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    let mut notifications = client.notifications();
+
+    let updated = Notification::new(
+        "notifications/resources/updated",
+        Some(serde_json::json!({ "uri": "file:///notes.txt" })),
+    );
+    let custom = Notification::new("custom/event", None);
+    let _ = server.send(Ok(Message::Notification(updated)));
+    let _ = server.send(Ok(Message::Notification(custom)));
+
+    let next = tokio::time::timeout(Duration::from_secs(1), notifications.next());
+    match next.await.expect("Expected a notification") {
+        Some(ServerNotification::ResourceUpdated(params)) => {
+            assert_eq!(params.uri, "file:///notes.txt")
+        }
+        other => panic!("Expected resource update, got {:?}", other),
+    }
+    let next = tokio::time::timeout(Duration::from_secs(1), notifications.next());
+    match next.await.expect("Expected a notification") {
+        Some(ServerNotification::Other(notification)) => {
+            assert_eq!(notification.method, "custom/event")
+        }
+        other => panic!("Expected custom notification, got {:?}", other),
+    }
+}
+
+/// Tests that per-method notification handlers only see their own method.
+#[tokio::test]
+async fn test_notification_handler_registration() {
+    use crate::client::ServerNotification;
+
+    let transport = EchoTransport::new();
+    let server = transport.sender.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    client.on_notification("notifications/tools/list_changed", move |notification| {
+        let _ = tx.send(notification.clone());
+    });
+
+    let _ = server.send(Ok(Message::Notification(Notification::new(
+        "notifications/prompts/list_changed",
+        None,
+    ))));
+    let _ = server.send(Ok(Message::Notification(Notification::new(
+        "notifications/tools/list_changed",
+        None,
+    ))));
+
+    let received = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("Handler should be called");
+    assert!(matches!(received, Some(ServerNotification::ToolListChanged)));
+    assert!(rx.try_recv().is_err(), "Handler should only see its own method");
+}
+
+/// Client handler exposing a single root.
+struct RootsHandler;
+
+#[async_trait]
+impl crate::client::ClientHandler for RootsHandler {
+    async fn list_roots(&self) -> Result<crate::types::ListRootsResult, Error> {
+        Ok(crate::types::ListRootsResult {
+            roots: vec![crate::types::Root {
+                uri: "file:///workspace".to_string(),
+                name: Some("workspace".to_string()),
+            }],
+        })
+    }
+}
+
+/// Sends `request` from the fake server and waits for the client's response.
+async fn answer_server_request(
+    transport_responses: &Arc<std::sync::Mutex<Vec<Response>>>,
+    server: &broadcast::Sender<Result<Message, Error>>,
+    request: crate::protocol::Request,
+) -> Response {
+    let id = request.id.clone();
+    let _ = server.send(Ok(Message::Request(request)));
+    for _ in 0..100 {
+        if let Some(response) = transport_responses
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id == id)
+        {
+            return response.clone();
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("Client did not answer request {}", id);
+}
+
+/// Tests that the client answers `ping` and rejects unknown methods without a handler.
+#[tokio::test]
+async fn test_server_requests_without_handler() {
+    use crate::protocol::{Request, RequestId};
+
+    let transport = EchoTransport::new();
+    let server = transport.sender.clone();
+    let responses = transport.responses.clone();
+    let _client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let ping = Request::new("ping", None, RequestId::String("s-1".to_string()));
+    let response = answer_server_request(&responses, &server, ping).await;
+    assert_eq!(response.result, Some(serde_json::json!({})));
+
+    let roots = Request::new("roots/list", None, RequestId::String("s-2".to_string()));
+    let response = answer_server_request(&responses, &server, roots).await;
+    assert_eq!(
+        response.error.map(|e| e.code),
+        Some(crate::error::ErrorCode::MethodNotFound as i32)
+    );
+}
+
+/// Tests that server requests are routed to the registered `ClientHandler`.
+#[tokio::test]
+async fn test_server_requests_with_handler() {
+    use crate::protocol::{Request, RequestId};
+
+    let transport = EchoTransport::new();
+    let server = transport.sender.clone();
+    let responses = transport.responses.clone();
+    let config = crate::client::ClientConfig {
+        handler: Some(Arc::new(RootsHandler)),
+        ..Default::default()
+    };
+    let _client = crate::client::Client::with_config(Arc::new(transport), None, None, config);
+
+    let roots = Request::new("roots/list", None, RequestId::Number(7));
+    let response = answer_server_request(&responses, &server, roots).await;
+    assert_eq!(
+        response.result,
+        Some(serde_json::json!({
+            "roots": [{ "uri": "file:///workspace", "name": "workspace" }]
+        }))
+    );
+
+    let sampling = Request::new("sampling/createMessage", None, RequestId::Number(8));
+    let response = answer_server_request(&responses, &server, sampling).await;
+    assert!(response.error.is_some(), "Unimplemented methods should return an error");
+}
+
+fn prompts_transport() -> EchoTransport {
+    EchoTransport::new()
+        .reply_with(
+            "prompts/list",
+            serde_json::json!({
+                "prompts": [{
+                    "name": "code_review",
+                    "description": "Asks the LLM to analyze code quality",
+                    "arguments": [
+                        { "name": "code", "description": "The code to review", "required": true },
+                        { "name": "style", "required": false }
+                    ]
+                }]
+            }),
+        )
+        .reply_with(
+            "prompts/get",
+            serde_json::json!({
+                "description": "Code review prompt",
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": "Please review this code" }
+                }]
+            }),
+        )
+}
+
+/// Tests listing prompts and getting a prompt with its required arguments.
+#[tokio::test]
+async fn test_list_and_get_prompt() -> Result<(), Error> {
+    let transport = prompts_transport();
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let prompts = client.list_prompts(None).await?;
+    assert_eq!(prompts.prompts.len(), 1);
+    assert_eq!(prompts.prompts[0].name, "code_review");
+    assert!(prompts.next_cursor.is_none());
+
+    let arguments = std::collections::HashMap::from([("code".to_string(), "fn main() {}".to_string())]);
+    let prompt = client.get_prompt("code_review", arguments).await?;
+    assert_eq!(prompt.messages.len(), 1);
+    assert_eq!(prompt.messages[0].role, "user");
+
+    let requests = requests.lock().unwrap();
+    let get = requests.iter().find(|r| r.method == "prompts/get").unwrap();
+    assert_eq!(
+        get.params,
+        Some(serde_json::json!({ "name": "code_review", "arguments": { "code": "fn main() {}" } }))
+    );
+    let listings = requests.iter().filter(|r| r.method == "prompts/list").count();
+    assert_eq!(listings, 1, "Getting a prompt should not list the prompts");
+    Ok(())
+}
+
+/// Tests that `get_prompt` rejects calls missing arguments the catalog lists as required
+/// without asking the server.
+#[tokio::test]
+async fn test_get_prompt_missing_required_argument() {
+    let transport = prompts_transport();
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    client.prompts().await.unwrap();
+
+    let arguments = std::collections::HashMap::from([("style".to_string(), "terse".to_string())]);
+    let result = client.get_prompt("code_review", arguments).await;
+    match result {
+        Err(Error::Protocol { code, data, .. }) => {
+            assert_eq!(code, crate::error::ErrorCode::InvalidParams);
+            assert_eq!(data, Some(serde_json::json!({ "missing": ["code"] })));
+        }
+        other => panic!("Expected invalid params error, got {:?}", other),
+    }
+    assert!(
+        !requests.lock().unwrap().iter().any(|r| r.method == "prompts/get"),
+        "Invalid calls should not reach the server"
+    );
+}
+
+/// Tests that `complete` sends a spec-shaped request and parses candidates.
+#[tokio::test]
+async fn test_complete_argument() -> Result<(), Error> {
+    use crate::types::CompletionReference;
+
+    let transport = EchoTransport::new().reply_with(
+        "completion/complete",
+        serde_json::json!({
+            "completion": { "values": ["python", "pytorch"], "total": 10, "hasMore": true }
+        }),
+    );
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let result = client
+        .complete(CompletionReference::resource("file:///{path}"), "path", "py")
+        .await?;
+    assert_eq!(result.completion.values, vec!["python", "pytorch"]);
+    assert_eq!(result.completion.total, Some(10));
+    assert_eq!(result.completion.has_more, Some(true));
+
+    let result = client
+        .complete(CompletionReference::prompt("code_review"), "language", "ru")
+        .await?;
+    assert_eq!(result.completion.values.len(), 2);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[0].params,
+        Some(serde_json::json!({
+            "ref": { "type": "ref/resource", "uri": "file:///{path}" },
+            "argument": { "name": "path", "value": "py" }
+        }))
+    );
+    assert_eq!(
+        requests[1].params.as_ref().unwrap()["ref"],
+        serde_json::json!({ "type": "ref/prompt", "name": "code_review" })
+    );
+    Ok(())
+}
+
+/// Tests that a resource subscription only yields updates for its URI, can re-read
+/// the resource, and unsubscribes when dropped.
+#[tokio::test]
+async fn test_resource_subscription() -> Result<(), Error> {
+    use futures::StreamExt;
+
+    let transport = EchoTransport::new()
+        .reply_with("resources/subscribe", serde_json::json!({}))
+        .reply_with("resources/unsubscribe", serde_json::json!({}))
+        .reply_with(
+            "resources/read",
+            serde_json::json!({
+                "contents": [{ "uri": "file:///a.txt", "mimeType": "text/plain", "text": "new" }]
+            }),
+        );
+    let server = transport.sender.clone();
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let updated = |uri: &str| {
+        Ok(Message::Notification(Notification::new(
+            "notifications/resources/updated",
+            Some(serde_json::json!({ "uri": uri })),
+        )))
+    };
+
+    let mut subscription = client.subscribe_resource("file:///a.txt").await?;
+    let _ = server.send(updated("file:///b.txt"));
+    let _ = server.send(updated("file:///a.txt"));
+    let update = tokio::time::timeout(Duration::from_secs(1), subscription.next())
+        .await
+        .expect("Expected an update");
+    assert_eq!(update.unwrap().uri, "file:///a.txt");
+
+    let mut contents = subscription.read_updates();
+    let _ = server.send(updated("file:///a.txt"));
+    let read = tokio::time::timeout(Duration::from_secs(1), contents.next())
+        .await
+        .expect("Expected re-read contents");
+    assert_eq!(read.unwrap()?.contents.len(), 1);
+
+    drop(contents);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let requests = requests.lock().unwrap();
+    let unsubscribe = requests
+        .iter()
+        .find(|r| r.method == "resources/unsubscribe")
+        .expect("Dropping the subscription should unsubscribe");
+    assert_eq!(unsubscribe.params, Some(serde_json::json!({ "uri": "file:///a.txt" })));
+    Ok(())
+}
+
+/// Tests that dropping one of two subscriptions to the same URI keeps the other
+/// receiving updates, and only the last drop unsubscribes.
+#[tokio::test]
+async fn test_resource_subscription_shared_uri() -> Result<(), Error> {
+    use futures::StreamExt;
+
+    let transport = EchoTransport::new()
+        .reply_with("resources/subscribe", serde_json::json!({}))
+        .reply_with("resources/unsubscribe", serde_json::json!({}));
+    let server = transport.sender.clone();
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    let unsubscribes = || {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == "resources/unsubscribe")
+            .count()
+    };
+
+    let first = client.subscribe_resource("file:///a.txt").await?;
+    let mut second = client.subscribe_resource("file:///a.txt").await?;
+
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(unsubscribes(), 0);
+
+    let _ = server.send(Ok(Message::Notification(Notification::new(
+        "notifications/resources/updated",
+        Some(serde_json::json!({ "uri": "file:///a.txt" })),
+    ))));
+    let update = tokio::time::timeout(Duration::from_secs(1), second.next())
+        .await
+        .expect("The remaining subscription should still get updates");
+    assert_eq!(update.unwrap().uri, "file:///a.txt");
+
+    drop(second);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(unsubscribes(), 1);
+    Ok(())
+}
+
+/// Tests listing resource templates and expanding one into a readable URI.
+#[tokio::test]
+async fn test_list_resource_templates() -> Result<(), Error> {
+    use crate::uri_template::TemplateValue;
+
+    let transport = EchoTransport::new().reply_with(
+        "resources/templates/list",
+        serde_json::json!({
+            "resourceTemplates": [{
+                "uriTemplate": "file:///{+path}",
+                "name": "Project files",
+                "mimeType": "text/plain"
+            }]
+        }),
+    );
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let result = client.list_resource_templates(None).await?;
+    assert_eq!(result.resource_templates.len(), 1);
+    assert!(result.next_cursor.is_none());
+
+    let template = result.resource_templates[0].parse_template()?;
+    let uri = template.expand(&std::collections::HashMap::from([(
+        "path".to_string(),
+        TemplateValue::from("src/main.rs"),
+    )]));
+    assert_eq!(uri, "file:///src/main.rs");
+    assert_eq!(template.match_uri(&uri).unwrap()["path"], "src/main.rs");
+
+    assert_eq!(
+        requests.lock().unwrap()[0].method,
+        "resources/templates/list"
+    );
+    Ok(())
+}
+
+/// Tests that the `*_all` and `*_paged` listings follow `nextCursor` across pages,
+/// and that lookups by name search beyond the first page.
+#[tokio::test]
+async fn test_list_pagination() -> Result<(), Error> {
+    use futures::TryStreamExt;
+
+    let tool = |name: &str| {
+        serde_json::json!({ "name": name, "description": name, "inputSchema": { "type": "object" } })
+    };
+    let transport = EchoTransport::new()
+        .reply_paged(
+            "tools/list",
+            vec![
+                serde_json::json!({ "tools": [tool("a"), tool("b")] }),
+                serde_json::json!({ "tools": [] }),
+                serde_json::json!({ "tools": [tool("c")] }),
+            ],
+        )
+        .reply_paged(
+            "prompts/list",
+            vec![
+                serde_json::json!({ "prompts": [{ "name": "first" }] }),
+                serde_json::json!({ "prompts": [{ "name": "second" }] }),
+            ],
+        );
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let first_page = client.list_tools(None).await?;
+    assert_eq!(first_page.tools.len(), 2);
+    assert_eq!(first_page.next_cursor.as_deref(), Some("1"));
+
+    let names: Vec<String> = client.list_tools_all().await?.into_iter().map(|t| t.name).collect();
+    assert_eq!(names, vec!["a", "b", "c"]);
+    let sent_cursors: Vec<_> = requests.lock().unwrap()[1..]
+        .iter()
+        .map(|r| r.params.as_ref().unwrap().get("cursor").cloned())
+        .collect();
+    assert_eq!(
+        sent_cursors,
+        vec![None, Some(serde_json::json!("1")), Some(serde_json::json!("2"))]
+    );
+
+    let mut paged = client.list_prompts_paged();
+    assert_eq!(paged.try_next().await?.unwrap().name, "first");
+    assert_eq!(paged.try_next().await?.unwrap().name, "second");
+    assert!(paged.try_next().await?.is_none());
+
+    assert!(client.get_tool("c").await?.is_some());
+    assert!(client.find_prompt("second").await?.is_some());
+    assert!(client.find_prompt("missing").await?.is_none());
+    Ok(())
+}
+
+/// Tests that a server which never stops paginating is cut off at the page limit.
+#[tokio::test]
+async fn test_list_pagination_page_limit() {
+    let transport = EchoTransport::new().reply_with(
+        "resources/list",
+        serde_json::json!({ "resources": [], "nextCursor": "0" }),
+    );
+    let requests = transport.requests.clone();
+    let config = crate::client::ClientConfig {
+        max_pages: 3,
+        ..Default::default()
+    };
+    let client = crate::client::Client::with_config(Arc::new(transport), None, None, config);
+
+    let err = client.list_resources_all().await.unwrap_err();
+    assert!(matches!(err, Error::TooManyPages { limit: 3, .. }));
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+/// Tests that `set_logging_level` sends the spec level name, and is refused locally
+/// when the server didn't announce logging support.
+#[tokio::test]
+async fn test_set_logging_level() -> Result<(), Error> {
+    use crate::types::LoggingLevel;
+
+    let transport = EchoTransport::new()
+        .reply_with(
+            "initialize",
+            serde_json::json!({
+                "protocolVersion": crate::LATEST_PROTOCOL_VERSION,
+                "serverInfo": { "name": "echo", "version": "1.0.0" },
+                "capabilities": { "logging": {} }
+            }),
+        )
+        .reply_with("logging/setLevel", serde_json::json!({}));
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    initialize(&client).await?;
+    assert_eq!(client.server_info().unwrap().name, "echo");
+
+    client.set_logging_level(LoggingLevel::Warning).await?;
+    let request = requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.method, "logging/setLevel");
+    assert_eq!(request.params, Some(serde_json::json!({ "level": "warning" })));
+
+    let transport = EchoTransport::new().reply_with(
+        "initialize",
+        serde_json::json!({
+            "protocolVersion": crate::LATEST_PROTOCOL_VERSION,
+            "serverInfo": { "name": "quiet", "version": "1.0.0" },
+            "capabilities": {}
+        }),
+    );
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    initialize(&client).await?;
+    let err = client.set_logging_level(LoggingLevel::Debug).await.unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Protocol {
+            code: crate::error::ErrorCode::MethodNotFound,
+            ..
+        }
+    ));
+    Ok(())
+}
+
+/// Tests that initialize proposes the configured protocol version, accepts a supported
+/// counter-offer, and rejects an unsupported one.
+#[tokio::test]
+async fn test_protocol_version_negotiation() -> Result<(), Error> {
+    use crate::types::{ClientCapabilities, Implementation};
+
+    let implementation = Implementation {
+        name: "test".to_string(),
+        title: None,
+        version: "0.1.0".to_string(),
+    };
+    let server_answering = |version: &str| {
+        EchoTransport::new().reply_with(
+            "initialize",
+            serde_json::json!({
+                "protocolVersion": version,
+                "serverInfo": { "name": "echo", "version": "1.0.0" },
+                "capabilities": {}
+            }),
+        )
+    };
+    let config = |version: crate::types::ProtocolVersion| crate::client::ClientConfig {
+        protocol_version: version,
+        ..Default::default()
+    };
+
+    // The server counters our older proposal with the latest version, which we support.
+    let transport = server_answering(crate::LATEST_PROTOCOL_VERSION);
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::with_config(
+        Arc::new(transport),
+        None,
+        None,
+        config(crate::types::ProtocolVersion::V2024_10_07),
+    );
+    assert!(client.protocol_version().is_none());
+    client
+        .initialize(implementation.clone(), ClientCapabilities::default())
+        .await?;
+    assert_eq!(
+        requests.lock().unwrap()[0].params.as_ref().unwrap()["protocolVersion"],
+        "2024-10-07"
+    );
+    assert_eq!(
+        client.protocol_version(),
+        Some(crate::types::ProtocolVersion::LATEST)
+    );
+
+    // The server answers with a version we don't know.
+    let client = crate::client::Client::new(Arc::new(server_answering("1999-01-01")), None, None);
+    let err = client
+        .initialize(implementation.clone(), ClientCapabilities::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::UnsupportedProtocolVersion { ref version, .. } if version == "1999-01-01"));
+    assert!(client.protocol_version().is_none());
+    Ok(())
+}
+
+struct NewerRevisionHandler;
+
+#[async_trait]
+impl crate::client::ClientHandler for NewerRevisionHandler {
+    async fn create_message(
+        &self,
+        _request: crate::types::CreateMessageRequest,
+    ) -> Result<crate::types::CreateMessageResult, Error> {
+        Ok(crate::types::CreateMessageResult {
+            role: "assistant".to_string(),
+            content: MessageContent::Audio {
+                data: "UklGRg==".to_string(),
+                mime_type: "audio/wav".to_string(),
+                annotations: None,
+                meta: None,
+            },
+            model: "test-model".to_string(),
+            stop_reason: None,
+        })
+    }
+
+    async fn elicit(
+        &self,
+        request: crate::types::ElicitRequest,
+    ) -> Result<crate::types::ElicitResult, Error> {
+        assert_eq!(request.message, "Which repository?");
+        Ok(crate::types::ElicitResult {
+            action: crate::types::ElicitAction::Accept,
+            content: Some(std::collections::HashMap::from([(
+                "repo".to_string(),
+                serde_json::json!("mcp_client_rust"),
+            )])),
+        })
+    }
+}
+
+/// Tests that elicitation requests reach the handler, and that answers are shaped
+/// for the negotiated revision (audio becomes text for a 2024-11-05 server).
+#[tokio::test]
+async fn test_newer_revision_server_requests() -> Result<(), Error> {
+    use crate::protocol::{Request, RequestId};
+    use crate::types::{ClientCapabilities, ElicitationCapability, Implementation};
+
+    let transport = EchoTransport::new().reply_with(
+        "initialize",
+        serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "serverInfo": { "name": "old", "version": "1.0.0" },
+            "capabilities": {}
+        }),
+    );
+    let server = transport.sender.clone();
+    let requests = transport.requests.clone();
+    let responses = transport.responses.clone();
+    let config = crate::client::ClientConfig {
+        handler: Some(Arc::new(NewerRevisionHandler)),
+        ..Default::default()
+    };
+    let client = crate::client::Client::with_config(Arc::new(transport), None, None, config);
+    let capabilities = ClientCapabilities {
+        elicitation: Some(ElicitationCapability {}),
+        ..Default::default()
+    };
+    let implementation = Implementation {
+        name: "test".to_string(),
+        title: Some("Test Client".to_string()),
+        version: "0.1.0".to_string(),
+    };
+    client.initialize(implementation, capabilities).await?;
+
+    // We proposed the latest revision, so its fields were sent.
+    let init = requests.lock().unwrap()[0].params.clone().unwrap();
+    assert_eq!(init["protocolVersion"], crate::LATEST_PROTOCOL_VERSION);
+    assert_eq!(init["capabilities"]["elicitation"], serde_json::json!({}));
+    assert_eq!(init["clientInfo"]["title"], "Test Client");
+
+    let elicit = Request::new(
+        "elicitation/create",
+        Some(serde_json::json!({
+            "message": "Which repository?",
+            "requestedSchema": { "type": "object", "properties": { "repo": { "type": "string" } } }
+        })),
+        RequestId::Number(1),
+    );
+    let response = answer_server_request(&responses, &server, elicit).await;
+    assert_eq!(
+        response.result,
+        Some(serde_json::json!({ "action": "accept", "content": { "repo": "mcp_client_rust" } }))
+    );
+
+    let sampling = Request::new(
+        "sampling/createMessage",
+        Some(serde_json::json!({ "messages": [], "maxTokens": 10 })),
+        RequestId::Number(2),
+    );
+    let response = answer_server_request(&responses, &server, sampling).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["content"]["type"], "text");
+    assert_eq!(result["content"]["text"], "[audio/wav audio omitted]");
+    Ok(())
+}
+
+/// Tests `call_tool_typed` against tools with and without an output schema, and that
+/// structured results breaking the schema are rejected.
+#[tokio::test]
+async fn test_call_tool_typed() -> Result<(), Error> {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct Weather {
+        temperature: f64,
+    }
+
+    let tools = serde_json::json!({
+        "tools": [
+            {
+                "name": "weather",
+                "inputSchema": { "type": "object" },
+                "outputSchema": {
+                    "type": "object",
+                    "properties": { "temperature": { "type": "number" } },
+                    "required": ["temperature"]
+                }
+            },
+            { "name": "echo", "inputSchema": { "type": "object" } }
+        ]
+    });
+    let weather_transport = |structured: serde_json::Value| {
+        EchoTransport::new()
+            .reply_with("tools/list", tools.clone())
+            .reply_with(
+                "tools/call",
+                serde_json::json!({
+                    "content": [{ "type": "text", "text": structured.to_string() }],
+                    "structuredContent": structured
+                }),
+            )
+    };
+
+    let transport = weather_transport(serde_json::json!({ "temperature": 21.5 }));
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    let weather: Weather = client
+        .call_tool_typed("weather", serde_json::json!({ "city": "Oslo" }))
+        .await?;
+    assert_eq!(weather, Weather { temperature: 21.5 });
+    let methods: Vec<String> = requests.lock().unwrap().iter().map(|r| r.method.clone()).collect();
+    assert_eq!(methods, vec!["tools/call"]);
+
+    // Without the tools in the catalog the result isn't validated, only deserialized.
+    let client = crate::client::Client::new(
+        Arc::new(weather_transport(serde_json::json!({ "temperature": "hot" }))),
+        None,
+        None,
+    );
+    let err = client
+        .call_tool_typed::<Weather>("weather", serde_json::json!({}))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Serialization(_)), "{:?}", err);
+    client.tools().await?;
+    let err = client
+        .call_tool_typed::<Weather>("weather", serde_json::json!({}))
+        .await
+        .unwrap_err();
+    match err {
+        Error::InvalidToolOutput { tool, violations } => {
+            assert_eq!(tool, "weather");
+            assert_eq!(violations[0].path, "/temperature");
+        }
+        other => panic!("Expected InvalidToolOutput, got {:?}", other),
+    }
+
+    // Plain calls never list the tools themselves, and are validated once the catalog
+    // holds the tools.
+    let transport = weather_transport(serde_json::json!({ "temperature": "hot" }));
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    client.call_tool("weather", serde_json::json!({})).await?;
+    client.tools().await?;
+    for _ in 0..2 {
+        let err = client
+            .call_tool("weather", serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidToolOutput { .. }), "{:?}", err);
+    }
+    let methods: Vec<String> = requests.lock().unwrap().iter().map(|r| r.method.clone()).collect();
+    assert_eq!(
+        methods,
+        vec!["tools/call", "tools/list", "tools/call", "tools/call"]
+    );
+
+    // Text content isn't taken for structured content.
+    let transport = EchoTransport::new().reply_with("tools/list", tools);
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    let err = client
+        .call_tool_typed::<Weather>("echo", serde_json::json!({ "temperature": -3.0 }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Other(_)), "{:?}", err);
+    Ok(())
+}
+
+/// Tests that, when enabled, tool arguments are checked against the input schema
+/// before anything is sent, reporting every failing path.
+#[tokio::test]
+async fn test_tool_argument_validation() -> Result<(), Error> {
+    let tools = serde_json::json!({
+        "tools": [{
+            "name": "add-note",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["name"]
+            }
+        }]
+    });
+    let transport = EchoTransport::new().reply_with("tools/list", tools);
+    let requests = transport.requests.clone();
+    let config = crate::client::ClientConfig {
+        validate_tool_arguments: true,
+        ..Default::default()
+    };
+    let client = crate::client::Client::with_config(Arc::new(transport), None, None, config);
+
+    let err = client
+        .call_tool("add-note", serde_json::json!({ "tags": ["ok", 7] }))
+        .await
+        .unwrap_err();
+    match err {
+        Error::InvalidToolArguments { tool, violations } => {
+            assert_eq!(tool, "add-note");
+            let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+            assert!(paths.contains(&""), "missing 'name': {:?}", violations);
+            assert!(paths.contains(&"/tags/1"), "non-string tag: {:?}", violations);
+        }
+        other => panic!("Expected InvalidToolArguments, got {:?}", other),
+    }
+    let methods = |requests: &Arc<std::sync::Mutex<Vec<crate::protocol::Request>>>| {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.method.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(methods(&requests), vec!["tools/list"]);
+
+    client
+        .call_tool("add-note", serde_json::json!({ "name": "todo", "tags": ["a"] }))
+        .await?;
+    // Unlisted tools are left for the server to judge, without listing again.
+    client.call_tool("unknown", serde_json::json!(42)).await?;
+    assert_eq!(methods(&requests), vec!["tools/list", "tools/call", "tools/call"]);
+    Ok(())
+}
+
+/// Tests that the catalog lists tools once, drops them when the server reports a
+/// change, and re-lists them straight away when auto refresh is enabled.
+#[tokio::test]
+async fn test_catalog_cache_and_invalidation() -> Result<(), Error> {
+    let tools = serde_json::json!({
+        "tools": [{ "name": "echo", "inputSchema": { "type": "object" } }]
+    });
+    let list_changed = || {
+        Ok(Message::Notification(Notification::new(
+            "notifications/tools/list_changed",
+            None,
+        )))
+    };
+    let count_lists = |requests: &Arc<std::sync::Mutex<Vec<crate::protocol::Request>>>| {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == "tools/list")
+            .count()
+    };
+
+    let transport = EchoTransport::new().reply_with("tools/list", tools.clone());
+    let server = transport.sender.clone();
+    let requests = transport.requests.clone();
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+    let mut catalog = client.catalog();
+    assert!(catalog.borrow_and_update().tools.is_none());
+
+    assert!(client.get_tool("echo").await?.is_some());
+    assert!(client.get_tool("missing").await?.is_none());
+    assert_eq!(client.tools().await?.len(), 1);
+    assert_eq!(count_lists(&requests), 1);
+    assert!(catalog.has_changed().unwrap());
+    assert_eq!(catalog.borrow_and_update().tools.as_ref().map(Vec::len), Some(1));
+
+    let _ = server.send(list_changed());
+    tokio::time::timeout(Duration::from_secs(1), catalog.changed())
+        .await
+        .expect("Catalog should be invalidated")
+        .unwrap();
+    assert!(catalog.borrow_and_update().tools.is_none());
+    client.tools().await?;
+    assert_eq!(count_lists(&requests), 2);
+
+    let transport = EchoTransport::new().reply_with("tools/list", tools);
+    let server = transport.sender.clone();
+    let requests = transport.requests.clone();
+    let config = crate::client::ClientConfig {
+        auto_refresh_catalog: true,
+        ..Default::default()
+    };
+    let client = crate::client::Client::with_config(Arc::new(transport), None, None, config);
+    client.tools().await?;
+    let mut catalog = client.catalog();
+    catalog.borrow_and_update();
+
+    let _ = server.send(list_changed());
+    tokio::time::timeout(Duration::from_secs(1), async {
+        loop {
+            catalog.changed().await.unwrap();
+            if catalog.borrow_and_update().tools.is_some() {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("Catalog should be refreshed");
+    assert_eq!(count_lists(&requests), 2);
+    Ok(())
+}
+
+/// Tests that a tool result with `isError` is returned whole as `Error::ToolExecution`,
+/// including content that isn't text.
+#[tokio::test]
+async fn test_tool_execution_error() {
+    let failed = serde_json::json!({
+        "content": [
+            { "type": "text", "text": "Disk full" },
+            { "type": "resource_link", "uri": "file:///var/log/app.log", "name": "app.log" }
+        ],
+        "isError": true
+    });
+    let transport = EchoTransport::new().reply_with("tools/call", failed);
+    let client = crate::client::Client::new(Arc::new(transport), None, None);
+
+    let err = client
+        .call_tool("save", serde_json::json!({}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Tool 'save' execution failed: Disk full");
+    match err {
+        Error::ToolExecution { tool, result } => {
+            assert_eq!(tool, "save");
+            assert!(result.is_error);
+            assert_eq!(result.content.len(), 2);
+            assert!(matches!(
+                &result.content[1],
+                crate::types::MessageContent::ResourceLink { name, .. } if name == "app.log"
+            ));
+        }
+        other => panic!("Expected ToolExecution, got {:?}", other),
+    }
+}
@@ -0,0 +1,75 @@
+use jsonschema::Validator;
+
+use crate::error::SchemaViolation;
+
+/// Compiles the JSON Schema `schema`, to validate any number of instances against.
+///
+/// A schema that doesn't compile can't be used to blame an instance, so it is
+/// logged and `None` returned, which callers treat as accepting everything.
+pub(crate) fn compile(schema: &serde_json::Value) -> Option<Validator> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => Some(validator),
+        Err(e) => {
+            tracing::warn!(error = %e, "Ignoring invalid JSON Schema");
+            None
+        }
+    }
+}
+
+/// Validates `instance` against a compiled schema, returning every violation found.
+pub(crate) fn validate(
+    validator: &Validator,
+    instance: &serde_json::Value,
+) -> Result<(), Vec<SchemaViolation>> {
+    let violations: Vec<SchemaViolation> = validator
+        .iter_errors(instance)
+        .map(|error| SchemaViolation {
+            path: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_paths_of_violations() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "temperature": { "type": "number" },
+                "readings": { "type": "array", "items": { "type": "integer" } }
+            },
+            "required": ["temperature"]
+        });
+        let validator = compile(&schema).unwrap();
+        assert!(validate(&validator, &serde_json::json!({ "temperature": 21.5 })).is_ok());
+
+        let violations =
+            validate(&validator, &serde_json::json!({ "readings": [1, "two"] })).unwrap_err();
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert!(
+            paths.contains(&""),
+            "missing required property: {:?}",
+            violations
+        );
+        assert!(
+            paths.contains(&"/readings/1"),
+            "wrong item type: {:?}",
+            violations
+        );
+    }
+
+    #[test]
+    fn test_invalid_schema_is_ignored() {
+        let schema = serde_json::json!({ "type": 42 });
+        assert!(compile(&schema).is_none());
+    }
+}
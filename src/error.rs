@@ -5,8 +5,11 @@
 //! MCP-specific error codes.
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::types::{CallToolResult, MessageContent};
+
 /// Error codes as defined in the MCP protocol.
 ///
 /// These error codes are based on the JSON-RPC 2.0 specification with additional
@@ -30,10 +33,14 @@ pub enum ErrorCode {
     /// Internal JSON-RPC error.
     InternalError = -32603,
 
+    /// The requested resource does not exist.
+    /// This error is returned by `resources/read` for an unknown URI.
+    ResourceNotFound = -32002,
+
     /// Server has not been initialized.
     /// This error is returned when a request is made before the server
     /// has been properly initialized.
-    ServerNotInitialized = -32002,
+    ServerNotInitialized = -32003,
 
     /// Unknown error code.
     /// This error is returned when an error code is received that is not
@@ -54,7 +61,8 @@ impl From<i32> for ErrorCode {
             -32601 => ErrorCode::MethodNotFound,
             -32602 => ErrorCode::InvalidParams,
             -32603 => ErrorCode::InternalError,
-            -32002 => ErrorCode::ServerNotInitialized,
+            -32002 => ErrorCode::ResourceNotFound,
+            -32003 => ErrorCode::ServerNotInitialized,
             -32001 => ErrorCode::UnknownErrorCode,
             -32000 => ErrorCode::RequestFailed,
             _ => ErrorCode::UnknownErrorCode,
@@ -100,11 +108,123 @@ pub enum Error {
     #[error("IO error: {0}")]
     Io(String),
 
+    /// No response arrived before the request's timeout elapsed
+    #[error("Request to '{method}' timed out after {elapsed:?}")]
+    Timeout {
+        /// The method of the request that timed out
+        method: String,
+        /// How long the client waited before giving up
+        elapsed: Duration,
+    },
+
+    /// The request was cancelled before a response arrived
+    #[error("Request to '{method}' was cancelled: {reason}")]
+    Cancelled {
+        /// The method of the cancelled request
+        method: String,
+        /// The reason given for the cancellation
+        reason: String,
+    },
+
+    /// The peer asked for or answered with a protocol version this SDK doesn't support
+    #[error("Unsupported protocol version '{version}' (supported: {})", supported.join(", "))]
+    UnsupportedProtocolVersion {
+        /// The offending protocol version
+        version: String,
+        /// The versions this SDK supports
+        supported: Vec<String>,
+    },
+
+    /// A paginated listing didn't end within the configured page limit
+    #[error("Listing '{method}' did not finish within {limit} pages")]
+    TooManyPages {
+        /// The list method being paginated
+        method: String,
+        /// The page limit that was reached
+        limit: usize,
+    },
+
+    /// Tool arguments don't match the tool's input schema; nothing was sent to the server
+    #[error(
+        "Arguments for tool '{tool}' do not match its inputSchema: {}",
+        SchemaViolation::join(violations)
+    )]
+    InvalidToolArguments {
+        /// The name of the tool
+        tool: String,
+        /// Every place where the arguments break the schema
+        violations: Vec<SchemaViolation>,
+    },
+
+    /// A tool's structured result doesn't match the output schema it declared
+    #[error(
+        "Tool '{tool}' returned output not matching its outputSchema: {}",
+        SchemaViolation::join(violations)
+    )]
+    InvalidToolOutput {
+        /// The name of the tool
+        tool: String,
+        /// Every place where the output breaks the schema
+        violations: Vec<SchemaViolation>,
+    },
+
+    /// The tool ran but reported a failure (`isError: true`). The complete result is
+    /// kept so its content can be handed back to the model.
+    #[error("Tool '{tool}' execution failed: {}", tool_error_text(result))]
+    ToolExecution {
+        /// The name of the tool
+        tool: String,
+        /// The result the tool returned, including every content part
+        result: Box<CallToolResult>,
+    },
+
     /// Other miscellaneous errors
     #[error("Other error: {0}")]
     Other(String),
 }
 
+/// A single place where a JSON value breaks a JSON Schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the offending value, empty for the root
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl SchemaViolation {
+    fn join(violations: &[SchemaViolation]) -> String {
+        violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// The text parts of a failed tool result, one per line.
+fn tool_error_text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
 impl Error {
     /// Create a new protocol error.
     ///
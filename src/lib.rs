@@ -2,14 +2,14 @@
 //!
 //! This SDK provides a Rust implementation of the Model Context Protocol (MCP), a protocol designed
 //! for communication between AI models and their runtime environments. The SDK supports both client
-//! and server implementations via a stdio-based transport layer.
+//! and server implementations over stdio, with a Streamable HTTP transport for remote servers.
 //!
 //! Located at https://github.com/darinkishore/mcp_client_rust
 //!
 //! ## Features
 //!
 //! - Full implementation of MCP protocol specification
-//! - Stdio transport layer
+//! - Stdio, TCP/Unix socket, Streamable HTTP, legacy HTTP+SSE and WebSocket transport layers
 //! - Async/await support using Tokio
 //! - Type-safe message handling
 //! - Comprehensive error handling
@@ -28,9 +28,9 @@
 //!     let transport = StdioTransport::with_streams(stdin(), stdout())?;
 //!     
 //!     // Create the client with Arc-wrapped transport
-//!     let client = Client::new(Arc::new(transport));
+//!     let client = Client::new(Arc::new(transport), None, None);
 //!     
-//!     // Use the client...
+//!     // Use the client; clones share the same connection and can be moved into other tasks...
 //!     
 //!     Ok(())
 //! }
@@ -48,10 +48,12 @@ pub mod error;
 pub mod protocol;
 /// Server module provides the MCP server implementation
 pub mod server;
-/// Transport layer implementations (stdio)
+/// Transport layer implementations (stdio, sockets, in-memory, Streamable HTTP, HTTP+SSE, WebSocket)
 pub mod transport;
 /// Common types used throughout the SDK
 pub mod types;
+/// RFC 6570 URI templates for resource templates
+pub mod uri_template;
 
 // Re-export commonly used types for convenience
 pub use error::Error;
@@ -62,14 +64,19 @@ pub use types::*;
 ///
 /// This version represents the most recent protocol specification that this SDK supports.
 /// It is used during client-server handshake to ensure compatibility.
-pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
 
 /// List of all protocol versions supported by this SDK
 ///
 /// This list is used during version negotiation to determine compatibility between
 /// client and server. The versions are listed in order of preference, with the
 /// most recent version first.
-pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[LATEST_PROTOCOL_VERSION, "2024-10-07"];
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[
+    LATEST_PROTOCOL_VERSION,
+    "2025-03-26",
+    "2024-11-05",
+    "2024-10-07",
+];
 
 /// JSON-RPC version used by the MCP protocol
 ///
@@ -3,9 +3,7 @@ use std::fmt;
 
 use crate::error::{Error, ErrorCode};
 
-pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
-pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[LATEST_PROTOCOL_VERSION, "2024-10-07"];
-pub const JSONRPC_VERSION: &str = "2.0";
+pub use crate::{JSONRPC_VERSION, LATEST_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS};
 
 /// A unique identifier for a request
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -126,6 +124,39 @@ impl From<Error> for ResponseError {
                 message: err.to_string(),
                 data: None,
             },
+            Error::UnsupportedProtocolVersion {
+                ref version,
+                ref supported,
+            } => ResponseError {
+                code: ErrorCode::InvalidParams.into(),
+                message: err.to_string(),
+                data: Some(serde_json::json!({
+                    "requested": version,
+                    "supported": supported,
+                })),
+            },
+            Error::Timeout { .. } | Error::Cancelled { .. } | Error::TooManyPages { .. } => {
+                ResponseError {
+                    code: ErrorCode::InternalError.into(),
+                    message: err.to_string(),
+                    data: None,
+                }
+            }
+            Error::InvalidToolArguments { .. } => ResponseError {
+                code: ErrorCode::InvalidParams.into(),
+                message: err.to_string(),
+                data: None,
+            },
+            Error::InvalidToolOutput { .. } => ResponseError {
+                code: ErrorCode::InternalError.into(),
+                message: err.to_string(),
+                data: None,
+            },
+            Error::ToolExecution { ref result, .. } => ResponseError {
+                code: ErrorCode::InternalError.into(),
+                message: err.to_string(),
+                data: serde_json::to_value(result).ok(),
+            },
             Error::Other(msg) => ResponseError {
                 code: ErrorCode::InternalError.into(),
                 message: msg,
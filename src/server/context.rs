@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use crate::{
+    error::Error,
+    protocol::{Notification, RequestId},
+    transport::{Message, Transport},
+    types::{Progress, ProgressNotification, ProgressToken},
+};
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+/// Information about the request a `ServerHandler` is currently handling.
+///
+/// Available through [`RequestContext::current`] from within `handle_method`
+/// (and anything it awaits), since each request runs in its own task.
+#[derive(Clone)]
+pub struct RequestContext {
+    request_id: RequestId,
+    progress_token: Option<ProgressToken>,
+    transport: Arc<dyn Transport>,
+}
+
+impl RequestContext {
+    pub(crate) fn new(
+        request_id: RequestId,
+        params: Option<&serde_json::Value>,
+        transport: Arc<dyn Transport>,
+    ) -> Self {
+        let progress_token = params
+            .and_then(|p| p.get("_meta"))
+            .and_then(|meta| meta.get("progressToken"))
+            .and_then(|token| serde_json::from_value(token.clone()).ok());
+        Self {
+            request_id,
+            progress_token,
+            transport,
+        }
+    }
+
+    /// Runs `future` with this context set as the current one.
+    pub(crate) async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        REQUEST_CONTEXT.scope(self, future).await
+    }
+
+    /// Returns the context of the request being handled, or `None` outside a request.
+    pub fn current() -> Option<RequestContext> {
+        REQUEST_CONTEXT.try_with(|context| context.clone()).ok()
+    }
+
+    /// The ID of the request being handled.
+    pub fn request_id(&self) -> &RequestId {
+        &self.request_id
+    }
+
+    /// The progress token the client attached to the request, if it asked for progress.
+    pub fn progress_token(&self) -> Option<&ProgressToken> {
+        self.progress_token.as_ref()
+    }
+
+    /// Sends a `notifications/progress` update for this request.
+    /// Does nothing if the client didn't ask for progress.
+    pub async fn report_progress(&self, progress: Progress) -> Result<(), Error> {
+        let Some(token) = self.progress_token.clone() else {
+            return Ok(());
+        };
+        let params = ProgressNotification {
+            progress_token: token,
+            progress,
+        };
+        let notification =
+            Notification::new("notifications/progress", Some(serde_json::to_value(params)?));
+        self.transport
+            .send(Message::Notification(notification))
+            .await
+    }
+}
@@ -1,15 +1,28 @@
 use async_trait::async_trait;
 use futures::StreamExt;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
 
 use crate::{
     error::{Error, ErrorCode},
-    protocol::{Notification, Request, RequestId, Response, ResponseError},
-    transport::{Message, Transport},
-    types::{ClientCapabilities, Implementation, ServerCapabilities},
+    protocol::{Request, RequestId, Response, ResponseError},
+    transport::{socket::Listener, Message, Transport},
+    types::{
+        CallToolResult, ClientCapabilities, GetPromptResult, Implementation, InitializeResult,
+        ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult, ListToolsResult,
+        ProtocolVersion, ReadResourceResult, ServerCapabilities, Versioned,
+    },
+    LATEST_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
 };
 
+mod context;
+mod resources;
+pub use context::RequestContext;
+pub use resources::ResourceRouter;
+
 /// Trait for implementing MCP server handlers
 #[async_trait]
 pub trait ServerHandler: Send + Sync {
@@ -23,7 +36,23 @@ pub trait ServerHandler: Send + Sync {
     /// Handle shutdown request
     async fn shutdown(&self) -> Result<(), Error>;
 
+    /// The name and version reported to clients as `serverInfo` during initialization.
+    ///
+    /// Defaults to this crate's name and version; override it to identify your server.
+    fn server_info(&self) -> Implementation {
+        Implementation {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            title: None,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
     /// Handle custom method calls
+    ///
+    /// Use [`RequestContext::current`] to learn about the request being handled,
+    /// e.g. to report progress back to the client. Results of the spec's list, call, get
+    /// and read methods may use the latest revision's fields; the server strips what a
+    /// client on an older revision can't express.
     async fn handle_method(
         &self,
         method: &str,
@@ -32,10 +61,15 @@ pub trait ServerHandler: Send + Sync {
 }
 
 /// Server state
+#[derive(Clone)]
 pub struct Server {
     transport: Arc<dyn Transport>,
     handler: Arc<dyn ServerHandler>,
     initialized: Arc<RwLock<bool>>,
+    /// The protocol version agreed on during `initialize`, used to shape results.
+    protocol_version: Arc<std::sync::RwLock<Option<ProtocolVersion>>>,
+    /// Requests currently being handled, so they can be aborted on cancellation.
+    in_flight: Arc<std::sync::Mutex<HashMap<RequestId, AbortHandle>>>,
 }
 
 impl Server {
@@ -45,29 +79,69 @@ impl Server {
             transport,
             handler,
             initialized: Arc::new(RwLock::new(false)),
+            protocol_version: Arc::new(std::sync::RwLock::new(None)),
+            in_flight: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Accepts clients from `listener`, serving each connection with its own `Server` in
+    /// the background. All connections share `handler`.
+    ///
+    /// Transient accept failures (an `Error::Transport`, see [`Listener::accept`]) are
+    /// logged and retried with a growing delay; any other error stops accepting and is
+    /// returned.
+    pub async fn serve(
+        listener: impl Listener,
+        handler: Arc<dyn ServerHandler>,
+    ) -> Result<(), Error> {
+        const MIN_BACKOFF: Duration = Duration::from_millis(10);
+        const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+        let mut backoff = MIN_BACKOFF;
+        loop {
+            let transport = match listener.accept().await {
+                Ok(transport) => transport,
+                Err(Error::Transport(e)) => {
+                    tracing::warn!(%e, ?backoff, "Failed to accept a client, retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            backoff = MIN_BACKOFF;
+
+            let server = Server::new(transport, handler.clone());
+            tokio::spawn(async move {
+                if let Err(e) = server.start().await {
+                    tracing::debug!(?e, "Client connection ended");
+                }
+            });
         }
     }
 
     /// Starts the server loop, receiving messages from the transport and processing them.
     /// The loop ends either if the transport closes, or if an error occurs.
+    ///
+    /// Each request is handled in its own task, so a slow handler doesn't block
+    /// other requests and can be aborted by a `notifications/cancelled` from the client.
     pub async fn start(&self) -> Result<(), Error> {
         let mut stream = self.transport.receive();
 
         while let Some(message) = stream.next().await {
             match message? {
                 Message::Request(request) => {
-                    let response = match self.handle_request(request.clone()).await {
-                        Ok(response) => response,
-                        Err(err) => Response::error(request.id, ResponseError::from(err)),
-                    };
-                    self.transport.send(Message::Response(response)).await?;
+                    self.spawn_request(request);
                 }
                 Message::Notification(notification) => {
                     match notification.method.as_str() {
                         "exit" => break,
-                        "initialized" => {
+                        "notifications/initialized" | "initialized" => {
                             *self.initialized.write().await = true;
                         }
+                        "notifications/cancelled" => {
+                            self.cancel_request(notification.params);
+                        }
                         _ => {
                             // Handle other notifications
                         }
@@ -86,6 +160,54 @@ impl Server {
         Ok(())
     }
 
+    /// Handles `request` in a background task and sends its response, unless cancelled first.
+    fn spawn_request(&self, request: Request) {
+        let server = self.clone();
+        let id = request.id.clone();
+        let context = RequestContext::new(id.clone(), request.params.as_ref(), self.transport.clone());
+
+        // The spec doesn't let clients cancel `initialize`, so it isn't registered.
+        let cancellable = request.method != "initialize";
+
+        // Hold the lock while spawning so the task can't deregister itself before it's registered.
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let task = tokio::spawn(async move {
+            let request_id = request.id.clone();
+            let response = match context.scope(server.handle_request(request)).await {
+                Ok(response) => response,
+                Err(err) => Response::error(request_id.clone(), ResponseError::from(err)),
+            };
+            server.in_flight.lock().unwrap().remove(&request_id);
+            if let Err(e) = server.transport.send(Message::Response(response)).await {
+                tracing::error!(?e, "Failed to send response");
+            }
+        });
+        if cancellable {
+            in_flight.insert(id, task.abort_handle());
+        }
+    }
+
+    /// Aborts the in-flight request named by a `notifications/cancelled` payload.
+    fn cancel_request(&self, params: Option<serde_json::Value>) {
+        let Some(params) = params else {
+            return;
+        };
+        let Ok(id) = serde_json::from_value::<RequestId>(params["requestId"].clone()) else {
+            tracing::debug!(?params, "Ignoring cancellation without a valid request ID");
+            return;
+        };
+
+        match self.in_flight.lock().unwrap().remove(&id) {
+            Some(task) => {
+                tracing::debug!(%id, reason = ?params.get("reason"), "Cancelling request");
+                task.abort();
+            }
+            None => {
+                tracing::debug!(%id, "Ignoring cancellation for unknown or finished request");
+            }
+        }
+    }
+
     async fn handle_request(&self, request: Request) -> Result<Response, Error> {
         let initialized = *self.initialized.read().await;
 
@@ -99,17 +221,34 @@ impl Server {
                 }
 
                 let params: serde_json::Value = request.params.unwrap_or(serde_json::json!({}));
-                let implementation: Implementation = serde_json::from_value(
-                    params.get("implementation").cloned().unwrap_or_default(),
-                )?;
+                // The spec calls it `clientInfo`; older clients of this SDK sent `implementation`.
+                let client_info = params
+                    .get("clientInfo")
+                    .or_else(|| params.get("implementation"));
+                let implementation: Implementation =
+                    serde_json::from_value(client_info.cloned().unwrap_or_default())?;
                 let capabilities: ClientCapabilities = serde_json::from_value(
                     params.get("capabilities").cloned().unwrap_or_default(),
                 )?;
 
-                let result = self
+                // Agree to the client's version if supported, otherwise offer our latest.
+                let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+                let protocol_version = requested
+                    .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+                    .unwrap_or(LATEST_PROTOCOL_VERSION);
+                let version: ProtocolVersion = protocol_version.parse()?;
+
+                let capabilities = self
                     .handler
                     .initialize(implementation, capabilities)
                     .await?;
+                *self.protocol_version.write().unwrap() = Some(version);
+                let result = InitializeResult {
+                    protocol_version: protocol_version.to_string(),
+                    server_info: self.handler.server_info(),
+                    capabilities,
+                }
+                .for_version(version);
                 Ok(Response::success(
                     request.id,
                     Some(serde_json::to_value(result)?),
@@ -138,17 +277,56 @@ impl Server {
                     .handler
                     .handle_method(&request.method, request.params)
                     .await?;
+                let result = self.for_negotiated_version(&request.method, result);
                 Ok(Response::success(request.id, Some(result)))
             }
         }
     }
+
+    /// Adapts a handler's result to the protocol version agreed on with the client, for
+    /// the methods whose results changed between revisions. Results for the latest
+    /// revision, of other methods, or that don't parse as the method's result type are
+    /// passed on unchanged.
+    fn for_negotiated_version(&self, method: &str, result: serde_json::Value) -> serde_json::Value {
+        let Some(version) = *self.protocol_version.read().unwrap() else {
+            return result;
+        };
+        if version >= ProtocolVersion::LATEST {
+            return result;
+        }
+        match method {
+            "tools/list" => shape::<ListToolsResult>(result, version),
+            "tools/call" => shape::<CallToolResult>(result, version),
+            "prompts/list" => shape::<ListPromptsResult>(result, version),
+            "prompts/get" => shape::<GetPromptResult>(result, version),
+            "resources/list" => shape::<ListResourcesResult>(result, version),
+            "resources/read" => shape::<ReadResourceResult>(result, version),
+            "resources/templates/list" => shape::<ListResourceTemplatesResult>(result, version),
+            _ => result,
+        }
+    }
+}
+
+/// Round-trips `result` through `T` to drop what `version` can't express.
+fn shape<T>(result: serde_json::Value, version: ProtocolVersion) -> serde_json::Value
+where
+    T: Versioned + serde::Serialize + serde::de::DeserializeOwned,
+{
+    match serde_json::from_value::<T>(result.clone()) {
+        Ok(typed) => serde_json::to_value(typed.for_version(version)).unwrap_or(result),
+        Err(e) => {
+            tracing::debug!(%e, "Sending result that doesn't parse as its type unchanged");
+            result
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use futures::{Stream, StreamExt};
+    use crate::protocol::{Notification, RequestId};
+    use futures::Stream;
     use std::{pin::Pin, time::Duration};
     use tokio::sync::{broadcast, mpsc};
 
@@ -194,12 +372,49 @@ mod tests {
         }
     }
 
+    /// Handler that reports two progress updates before answering.
+    struct ProgressHandler;
+
+    #[async_trait]
+    impl ServerHandler for ProgressHandler {
+        async fn initialize(
+            &self,
+            _implementation: Implementation,
+            _capabilities: ClientCapabilities,
+        ) -> Result<ServerCapabilities, Error> {
+            Ok(ServerCapabilities::default())
+        }
+
+        async fn shutdown(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn handle_method(
+            &self,
+            _method: &str,
+            _params: Option<serde_json::Value>,
+        ) -> Result<serde_json::Value, Error> {
+            let context = RequestContext::current().expect("called within a request");
+            for step in 1..=2 {
+                context
+                    .report_progress(crate::types::Progress {
+                        progress: step as f64,
+                        total: Some(2.0),
+                        message: None,
+                    })
+                    .await?;
+            }
+            Ok(serde_json::json!({"status": "done"}))
+        }
+    }
+
     struct MockTransport {
         client_to_server: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Result<Message, Error>>>>,
         server_to_client: broadcast::Sender<Result<Message, Error>>,
     }
 
     impl MockTransport {
+        #[allow(clippy::type_complexity)]
         fn new() -> (
             Self,
             mpsc::UnboundedSender<Result<Message, Error>>,
@@ -599,10 +814,143 @@ mod tests {
         let _ = tokio::time::timeout(Duration::from_secs(1), server_handle).await;
     }
 
+    #[tokio::test]
+    async fn test_request_cancellation() {
+        // Create transport and server with a slow method handler
+        let (transport, client_tx, mut client_rx) = MockTransport::new();
+        let handler = TestHandler::new(
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+            Duration::from_secs(2),
+        );
+        let server = Server::new(Arc::new(transport), Arc::new(handler));
+
+        // Start server in background
+        let server_handle = tokio::spawn(async move {
+            if let Err(e) = server.start().await {
+                tracing::warn!("Server error: {}", e);
+            }
+        });
+
+        // Initialize
+        let init_request = Request::new(
+            "initialize",
+            Some(serde_json::json!({
+                "implementation": {
+                    "name": "test-client",
+                    "version": "0.1.0"
+                },
+                "capabilities": {},
+                "protocolVersion": "2024-11-05"
+            })),
+            RequestId::Number(1),
+        );
+        let _ = client_tx.send(Ok(Message::Request(init_request)));
+
+        // Cancelling initialize is ignored
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new(
+            "notifications/cancelled",
+            Some(serde_json::json!({ "requestId": 1 })),
+        ))));
+        let result = tokio::time::timeout(Duration::from_secs(1), client_rx.recv()).await;
+        assert!(
+            matches!(result, Ok(Ok(Ok(Message::Response(ref r)))) if r.result.is_some()),
+            "Initialize should be answered despite the cancellation, got {:?}",
+            result
+        );
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new(
+            "initialized",
+            None,
+        ))));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Start a slow request, then cancel it
+        let method_request = Request::new("test_method", None, RequestId::Number(2));
+        let _ = client_tx.send(Ok(Message::Request(method_request)));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new(
+            "notifications/cancelled",
+            Some(serde_json::json!({ "requestId": 2, "reason": "no longer needed" })),
+        ))));
+
+        // The cancelled request must not produce a response
+        let result = tokio::time::timeout(Duration::from_secs(3), client_rx.recv()).await;
+        assert!(result.is_err(), "Cancelled request should not be answered");
+
+        // Cleanup
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new("exit", None))));
+        let _ = tokio::time::timeout(Duration::from_secs(1), server_handle).await;
+    }
+
+    #[tokio::test]
+    async fn test_progress_reporting() {
+        let (transport, client_tx, mut client_rx) = MockTransport::new();
+        let server = Server::new(Arc::new(transport), Arc::new(ProgressHandler));
+
+        // Start server in background
+        let server_handle = tokio::spawn(async move {
+            if let Err(e) = server.start().await {
+                tracing::warn!("Server error: {}", e);
+            }
+        });
+
+        // Initialize
+        let init_request = Request::new(
+            "initialize",
+            Some(serde_json::json!({
+                "implementation": {
+                    "name": "test-client",
+                    "version": "0.1.0"
+                },
+                "capabilities": {},
+                "protocolVersion": "2024-11-05"
+            })),
+            RequestId::Number(1),
+        );
+        let _ = client_tx.send(Ok(Message::Request(init_request)));
+        let _ = client_rx.recv().await;
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new(
+            "initialized",
+            None,
+        ))));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Request with a progress token
+        let method_request = Request::new(
+            "long_task",
+            Some(serde_json::json!({ "_meta": { "progressToken": "task-1" } })),
+            RequestId::Number(2),
+        );
+        let _ = client_tx.send(Ok(Message::Request(method_request)));
+
+        for step in 1..=2 {
+            match client_rx.recv().await {
+                Ok(Ok(Message::Notification(notification))) => {
+                    assert_eq!(notification.method, "notifications/progress");
+                    let params = notification.params.unwrap();
+                    assert_eq!(params["progressToken"], "task-1");
+                    assert_eq!(params["progress"], step as f64);
+                }
+                other => panic!("Expected progress notification, got {:?}", other),
+            }
+        }
+        match client_rx.recv().await {
+            Ok(Ok(Message::Response(response))) => {
+                assert_eq!(response.result, Some(serde_json::json!({"status": "done"})));
+            }
+            other => panic!("Expected response, got {:?}", other),
+        }
+
+        // Cleanup
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new("exit", None))));
+        let _ = tokio::time::timeout(Duration::from_secs(1), server_handle).await;
+    }
+
     #[tokio::test]
     async fn test_invalid_message_handling() {
         // Create transport and server
-        let (transport, client_tx, mut client_rx) = MockTransport::new();
+        let (transport, client_tx, _client_rx) = MockTransport::new();
         let handler = TestHandler::new(
             Duration::from_millis(100),
             Duration::from_millis(100),
@@ -634,4 +982,222 @@ mod tests {
         // Cleanup
         let _ = client_tx.send(Ok(Message::Notification(Notification::new("exit", None))));
     }
+
+    /// Sends `initialize` with `params` to a fresh server and returns its result.
+    async fn initialize_with(params: serde_json::Value) -> serde_json::Value {
+        let (transport, client_tx, mut client_rx) = MockTransport::new();
+        let handler = TestHandler::new(Duration::ZERO, Duration::ZERO, Duration::ZERO);
+        let server = Server::new(Arc::new(transport), Arc::new(handler));
+        let server_handle = tokio::spawn(async move { server.start().await });
+
+        let init_request = Request::new("initialize", Some(params), RequestId::Number(1));
+        let _ = client_tx.send(Ok(Message::Request(init_request)));
+        let result = tokio::time::timeout(Duration::from_secs(1), client_rx.recv()).await;
+
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new("exit", None))));
+        let _ = tokio::time::timeout(Duration::from_secs(1), server_handle).await;
+        match result {
+            Ok(Ok(Ok(Message::Response(response)))) => response.result.expect("initialize result"),
+            other => panic!("Expected an initialize response, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_protocol_version_negotiation() {
+        // An older supported version is agreed to
+        let result = initialize_with(serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "clientInfo": { "name": "test-client", "version": "0.1.0" },
+            "capabilities": {}
+        }))
+        .await;
+        assert_eq!(result["protocolVersion"], "2024-11-05");
+        assert_eq!(result["serverInfo"]["name"], env!("CARGO_PKG_NAME"));
+        assert!(result["capabilities"].is_object());
+
+        // An unsupported version is refused by offering our latest instead
+        let result = initialize_with(serde_json::json!({
+            "protocolVersion": "1999-01-01",
+            "clientInfo": { "name": "test-client", "version": "0.1.0" },
+            "capabilities": {}
+        }))
+        .await;
+        assert_eq!(result["protocolVersion"], LATEST_PROTOCOL_VERSION);
+
+        // So is a missing one, from clients predating negotiation
+        let result = initialize_with(serde_json::json!({
+            "implementation": { "name": "test-client", "version": "0.1.0" },
+            "capabilities": {}
+        }))
+        .await;
+        assert_eq!(result["protocolVersion"], LATEST_PROTOCOL_VERSION);
+    }
+
+    /// Handler answering with results that use fields of the latest revision.
+    struct NewRevisionHandler;
+
+    #[async_trait]
+    impl ServerHandler for NewRevisionHandler {
+        async fn initialize(
+            &self,
+            _implementation: Implementation,
+            _capabilities: ClientCapabilities,
+        ) -> Result<ServerCapabilities, Error> {
+            Ok(ServerCapabilities {
+                completions: Some(Default::default()),
+                ..Default::default()
+            })
+        }
+
+        async fn shutdown(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn server_info(&self) -> Implementation {
+            Implementation {
+                name: "new-server".to_string(),
+                title: Some("New Server".to_string()),
+                version: "1.0.0".to_string(),
+            }
+        }
+
+        async fn handle_method(
+            &self,
+            method: &str,
+            _params: Option<serde_json::Value>,
+        ) -> Result<serde_json::Value, Error> {
+            Ok(match method {
+                "tools/list" => serde_json::json!({
+                    "tools": [{
+                        "name": "weather",
+                        "title": "Weather",
+                        "inputSchema": { "type": "object" },
+                        "outputSchema": { "type": "object" }
+                    }]
+                }),
+                "tools/call" => serde_json::json!({
+                    "content": [{ "type": "audio", "data": "AAAA", "mimeType": "audio/wav" }],
+                    "structuredContent": { "temperature": 21.5 }
+                }),
+                "prompts/get" => serde_json::json!({
+                    "messages": [
+                        {
+                            "role": "user",
+                            "content": {
+                                "type": "text",
+                                "text": "Hello",
+                                "annotations": {
+                                    "priority": 1.0,
+                                    "lastModified": "2025-01-01T00:00:00Z"
+                                },
+                                "_meta": { "example.com/trace": "abc" }
+                            }
+                        },
+                        {
+                            "role": "user",
+                            "content": {
+                                "type": "resource",
+                                "resource": {
+                                    "uri": "file:///notes.txt",
+                                    "text": "Notes",
+                                    "_meta": { "example.com/trace": "abc" }
+                                }
+                            }
+                        }
+                    ]
+                }),
+                "resources/read" => serde_json::json!({
+                    "contents": [{
+                        "uri": "file:///notes.txt",
+                        "text": "Notes",
+                        "_meta": { "example.com/trace": "abc" }
+                    }]
+                }),
+                _ => serde_json::json!({ "custom": { "title": "kept" } }),
+            })
+        }
+    }
+
+    /// Initializes a `NewRevisionHandler` server at `version` and returns its answers to
+    /// `initialize` and to each of `methods`.
+    async fn results_for_version(version: &str, methods: &[&str]) -> Vec<serde_json::Value> {
+        let (transport, client_tx, mut client_rx) = MockTransport::new();
+        let server = Server::new(Arc::new(transport), Arc::new(NewRevisionHandler));
+        let server_handle = tokio::spawn(async move { server.start().await });
+
+        let mut results = Vec::new();
+        let params = serde_json::json!({
+            "protocolVersion": version,
+            "clientInfo": { "name": "test-client", "version": "0.1.0" },
+            "capabilities": {}
+        });
+        let requests = std::iter::once(("initialize", Some(params)))
+            .chain(methods.iter().map(|method| (*method, None)));
+        for (id, (method, params)) in requests.enumerate() {
+            let request = Request::new(method, params, RequestId::Number(id as i64));
+            let _ = client_tx.send(Ok(Message::Request(request)));
+            match tokio::time::timeout(Duration::from_secs(1), client_rx.recv()).await {
+                Ok(Ok(Ok(Message::Response(response)))) => results.push(response.result.unwrap()),
+                other => panic!("Expected a response to {}, got {:?}", method, other),
+            }
+            if method == "initialize" {
+                let initialized = Notification::new("notifications/initialized", None);
+                let _ = client_tx.send(Ok(Message::Notification(initialized)));
+            }
+        }
+
+        let _ = client_tx.send(Ok(Message::Notification(Notification::new("exit", None))));
+        let _ = tokio::time::timeout(Duration::from_secs(1), server_handle).await;
+        results
+    }
+
+    /// Tests that results are shaped for the negotiated version, and left alone for the latest.
+    #[tokio::test]
+    async fn test_results_shaped_for_negotiated_version() {
+        let methods = [
+            "tools/list",
+            "tools/call",
+            "prompts/get",
+            "resources/read",
+            "custom",
+        ];
+
+        let results = results_for_version("2024-11-05", &methods).await;
+        assert!(results[0]["serverInfo"].get("title").is_none());
+        assert!(results[0]["capabilities"].get("completions").is_none());
+        let tool = &results[1]["tools"][0];
+        assert_eq!(tool["name"], "weather");
+        assert!(tool.get("title").is_none());
+        assert!(tool.get("outputSchema").is_none());
+        let call = &results[2];
+        assert!(call.get("structuredContent").is_none());
+        assert_eq!(call["content"][0]["type"], "text");
+        let text = &results[3]["messages"][0]["content"];
+        assert!(text.get("_meta").is_none());
+        assert_eq!(text["annotations"]["priority"], 1.0);
+        assert!(text["annotations"].get("lastModified").is_none());
+        let embedded = &results[3]["messages"][1]["content"]["resource"];
+        assert_eq!(embedded["text"], "Notes");
+        assert!(embedded.get("_meta").is_none());
+        assert!(results[4]["contents"][0].get("_meta").is_none());
+        assert_eq!(results[5]["custom"]["title"], "kept");
+
+        // Completions date from 2025-03-26, `_meta` and `lastModified` from 2025-06-18.
+        let results = results_for_version("2025-03-26", &methods).await;
+        assert!(results[0]["capabilities"]["completions"].is_object());
+        assert!(results[3]["messages"][0]["content"].get("_meta").is_none());
+
+        let results = results_for_version(LATEST_PROTOCOL_VERSION, &methods).await;
+        assert_eq!(results[0]["serverInfo"]["title"], "New Server");
+        assert!(results[0]["capabilities"]["completions"].is_object());
+        assert_eq!(results[1]["tools"][0]["title"], "Weather");
+        assert_eq!(results[2]["structuredContent"]["temperature"], 21.5);
+        assert_eq!(results[2]["content"][0]["type"], "audio");
+        let text = &results[3]["messages"][0]["content"];
+        assert_eq!(text["_meta"]["example.com/trace"], "abc");
+        assert_eq!(text["annotations"]["lastModified"], "2025-01-01T00:00:00Z");
+        let embedded = &results[3]["messages"][1]["content"]["resource"];
+        assert_eq!(embedded["_meta"]["example.com/trace"], "abc");
+        assert_eq!(results[4]["contents"][0]["_meta"]["example.com/trace"], "abc");
+    }
 }
@@ -0,0 +1,144 @@
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, ErrorCode},
+    types::{ListResourceTemplatesResult, ReadResourceResult, ResourceTemplate},
+    uri_template::UriTemplate,
+};
+
+type ReadHandler = Arc<
+    dyn Fn(String, HashMap<String, String>) -> BoxFuture<'static, Result<ReadResourceResult, Error>>
+        + Send
+        + Sync,
+>;
+
+/// Routes `resources/read` requests to handlers by matching the URI against resource templates.
+///
+/// A `ServerHandler` can hold one of these and delegate `resources/templates/list` to
+/// [`ResourceRouter::list_templates`] and `resources/read` to [`ResourceRouter::read`].
+/// Templates are tried in registration order; the first match wins.
+#[derive(Clone, Default)]
+pub struct ResourceRouter {
+    routes: Vec<(ResourceTemplate, UriTemplate, ReadHandler)>,
+}
+
+impl ResourceRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for every URI matching `template`. The handler receives the
+    /// requested URI and the variable values extracted from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ErrorCode::InvalidParams` if the template's URI template doesn't parse.
+    pub fn route<F, Fut>(mut self, template: ResourceTemplate, handler: F) -> Result<Self, Error>
+    where
+        F: Fn(String, HashMap<String, String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, Error>> + Send + 'static,
+    {
+        let parsed = template.parse_template()?;
+        let handler: ReadHandler = Arc::new(move |uri, vars| Box::pin(handler(uri, vars)));
+        self.routes.push((template, parsed, handler));
+        Ok(self)
+    }
+
+    /// The registered templates, as a `resources/templates/list` result.
+    pub fn list_templates(&self) -> ListResourceTemplatesResult {
+        ListResourceTemplatesResult {
+            resource_templates: self.routes.iter().map(|(t, _, _)| t.clone()).collect(),
+            next_cursor: None,
+        }
+    }
+
+    /// Reads `uri` with the handler of the first template matching it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ErrorCode::ResourceNotFound` if no template matches, or whatever the handler returns.
+    pub async fn read(&self, uri: &str) -> Result<ReadResourceResult, Error> {
+        for (_, template, handler) in &self.routes {
+            if let Some(variables) = template.match_uri(uri) {
+                tracing::debug!(%uri, template = %template, ?variables, "Routing resource read");
+                return handler(uri.to_string(), variables).await;
+            }
+        }
+        Err(Error::protocol(
+            ErrorCode::ResourceNotFound,
+            format!("Resource not found: {}", uri),
+        )
+        .with_data(serde_json::json!({ "uri": uri })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ResourceContents;
+
+    fn template(uri_template: &str, name: &str) -> ResourceTemplate {
+        ResourceTemplate {
+            uri_template: uri_template.to_string(),
+            name: name.to_string(),
+            description: None,
+            mime_type: Some("text/plain".to_string()),
+            title: None,
+            meta: None,
+        }
+    }
+
+    fn text(uri: String, text: String) -> Result<ReadResourceResult, Error> {
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::Text {
+                uri,
+                mime_type: Some("text/plain".to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_routes_reads_by_template() {
+        let router = ResourceRouter::new()
+            .route(
+                template("users://{id}/profile", "profile"),
+                |uri, vars| async move { text(uri, format!("profile of {}", vars["id"])) },
+            )
+            .unwrap()
+            .route(
+                template("file:///{+path}", "file"),
+                |uri, vars| async move { text(uri, format!("file at {}", vars["path"])) },
+            )
+            .unwrap();
+
+        assert_eq!(router.list_templates().resource_templates.len(), 2);
+
+        let result = router.read("users://42/profile").await.unwrap();
+        let ResourceContents::Text { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(text, "profile of 42");
+
+        let result = router.read("file:///src/lib.rs").await.unwrap();
+        let ResourceContents::Text { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(text, "file at src/lib.rs");
+
+        for uri in ["users://42/settings", "users:///profile"] {
+            let err = router.read(uri).await.unwrap_err();
+            assert!(matches!(
+                err,
+                Error::Protocol {
+                    code: ErrorCode::ResourceNotFound,
+                    ..
+                }
+            ));
+        }
+    }
+}
@@ -0,0 +1,662 @@
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{Stream, StreamExt};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{StatusCode, Url};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::{
+    error::Error,
+    protocol::{RequestId, Response, ResponseError},
+    transport::{Message, Transport},
+};
+
+/// Header carrying the session the server assigned during initialization.
+pub const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+/// Header carrying the negotiated protocol version on every request after initialization.
+pub const PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+
+/// A transport speaking the Streamable HTTP protocol of remote MCP servers.
+///
+/// Every outgoing message is POSTed to a single endpoint. The server answers with either
+/// a JSON body or an SSE stream, and both are surfaced through [`Transport::receive`].
+/// Once the client sends `notifications/initialized`, a GET SSE stream is opened for
+/// messages the server initiates on its own, if the server offers one.
+///
+/// Each response is read on its own, so a response that can't be read only fails the
+/// request it answers, and unreadable messages the server sends unprompted are logged
+/// and skipped; the `receive` stream itself never fails.
+pub struct StreamableHttpTransport {
+    http: reqwest::Client,
+    url: Url,
+    shared: Arc<Shared>,
+    /// Messages from every response, kept until read.
+    incoming: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>>,
+    /// The GET stream of server-initiated messages, if open.
+    event_stream: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// State shared with the tasks reading response streams.
+struct Shared {
+    sender: mpsc::UnboundedSender<Message>,
+    session_id: RwLock<Option<String>>,
+    protocol_version: RwLock<Option<String>>,
+}
+
+impl StreamableHttpTransport {
+    /// Creates a transport posting to the MCP endpoint at `url`, e.g. `https://example.com/mcp`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Transport` if `url` is not a valid URL.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        Self::with_client(url, reqwest::Client::new())
+    }
+
+    /// Creates a transport using a preconfigured HTTP client, e.g. one with default
+    /// headers for authentication.
+    pub fn with_client(url: &str, http: reqwest::Client) -> Result<Self, Error> {
+        let url = Url::parse(url).map_err(|e| Error::Transport(format!("Invalid URL: {}", e)))?;
+        let (sender, incoming) = mpsc::unbounded_channel();
+        Ok(Self {
+            http,
+            url,
+            shared: Arc::new(Shared {
+                sender,
+                session_id: RwLock::new(None),
+                protocol_version: RwLock::new(None),
+            }),
+            incoming: Arc::new(tokio::sync::Mutex::new(incoming)),
+            event_stream: Mutex::new(None),
+        })
+    }
+
+    /// The session id the server assigned, if any.
+    pub fn session_id(&self) -> Option<String> {
+        self.shared.session_id.read().unwrap().clone()
+    }
+
+    /// Adds the session and protocol version headers to a request.
+    fn with_session(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let mut request = request;
+        if let Some(session_id) = self.shared.session_id.read().unwrap().as_deref() {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        if let Some(version) = self.shared.protocol_version.read().unwrap().as_deref() {
+            request = request.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        request
+    }
+
+    /// Opens the GET stream of server-initiated messages in the background.
+    fn open_event_stream(&self) {
+        let request = self
+            .with_session(self.http.get(self.url.clone()))
+            .header(ACCEPT, "text/event-stream");
+        let shared = self.shared.clone();
+        let task = tokio::spawn(async move {
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to open server event stream");
+                    return;
+                }
+            };
+            if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+                tracing::debug!("Server does not offer an event stream");
+                return;
+            }
+            if !response.status().is_success() {
+                tracing::warn!(status = %response.status(), "Server rejected event stream");
+                return;
+            }
+            shared.forward_events(response, None, None).await;
+            tracing::debug!("Server event stream ended");
+        });
+        if let Some(previous) = self.event_stream.lock().unwrap().replace(task) {
+            previous.abort();
+        }
+    }
+}
+
+impl Shared {
+    /// Passes a message on to receivers, noting the protocol version from the
+    /// response to `initialize`.
+    fn deliver(&self, message: Message, initialize_id: Option<&RequestId>) {
+        if let Message::Response(response) = &message {
+            if Some(&response.id) == initialize_id {
+                let version = response
+                    .result
+                    .as_ref()
+                    .and_then(|result| result.get("protocolVersion"))
+                    .and_then(|version| version.as_str());
+                if let Some(version) = version {
+                    *self.protocol_version.write().unwrap() = Some(version.to_string());
+                }
+            }
+        }
+        let _ = self.sender.send(message);
+    }
+
+    /// Fails the request `request_id` with `error`, or just logs it if the failed
+    /// response didn't answer a request.
+    fn fail(&self, request_id: Option<RequestId>, error: Error) {
+        match request_id {
+            Some(id) => {
+                tracing::warn!(%id, ?error, "Failed to read response");
+                let response = Response::error(id, ResponseError::from(error));
+                let _ = self.sender.send(Message::Response(response));
+            }
+            None => tracing::warn!(?error, "Failed to read message from server"),
+        }
+    }
+
+    /// Delivers every JSON-RPC message of a JSON body, which may be a single message or a batch.
+    fn deliver_body(&self, body: &[u8], initialize_id: Option<&RequestId>) -> Result<(), Error> {
+        let value: serde_json::Value = serde_json::from_slice(body)?;
+        let messages = match value {
+            serde_json::Value::Array(batch) => batch,
+            message => vec![message],
+        };
+        for message in messages {
+            self.deliver(serde_json::from_value(message)?, initialize_id);
+        }
+        Ok(())
+    }
+
+    /// Delivers the messages of an SSE response until the stream ends. If the stream
+    /// breaks or ends before answering `request_id`, that request fails.
+    async fn forward_events(
+        &self,
+        response: reqwest::Response,
+        request_id: Option<RequestId>,
+        initialize_id: Option<RequestId>,
+    ) {
+        let mut events = response.bytes_stream().eventsource();
+        let mut answered = false;
+        while let Some(event) = events.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    let request_id = request_id.filter(|_| !answered);
+                    self.fail(request_id, Error::Transport(e.to_string()));
+                    return;
+                }
+            };
+            if event.event != "message" || event.data.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Message>(&event.data) {
+                Ok(message) => {
+                    if let Message::Response(response) = &message {
+                        answered |= Some(&response.id) == request_id.as_ref();
+                    }
+                    self.deliver(message, initialize_id.as_ref());
+                }
+                Err(err) => {
+                    tracing::warn!(data = %event.data, %err, "Skipping unreadable event");
+                }
+            }
+        }
+        if let Some(request_id) = request_id.filter(|_| !answered) {
+            let error = Error::Transport("Event stream ended without a response".to_string());
+            self.fail(Some(request_id), error);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for StreamableHttpTransport {
+    /// POSTs a message to the endpoint. Any messages the server answers with, inline
+    /// or streamed, are delivered through `receive`.
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        let request_id = match &message {
+            Message::Request(request) => Some(request.id.clone()),
+            _ => None,
+        };
+        let initialize_id = match &message {
+            // Its response carries the protocol version to send on later requests.
+            Message::Request(request) if request.method == "initialize" => Some(request.id.clone()),
+            _ => None,
+        };
+        let initialized = matches!(
+            &message,
+            Message::Notification(notification) if notification.method == "notifications/initialized"
+        );
+
+        let response = self
+            .with_session(self.http.post(self.url.clone()))
+            .header(ACCEPT, "application/json, text/event-stream")
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&message)?)
+            .send()
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+
+        if let Some(session_id) = response.headers().get(SESSION_ID_HEADER) {
+            let session_id = session_id
+                .to_str()
+                .map_err(|e| Error::Transport(format!("Invalid session id: {}", e)))?;
+            *self.shared.session_id.write().unwrap() = Some(session_id.to_string());
+        }
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND && self.session_id().is_some() {
+            return Err(Error::Transport("MCP session expired".to_string()));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Transport(format!("HTTP {}: {}", status, body)));
+        }
+
+        if initialized {
+            self.open_event_stream();
+        }
+        if status == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if content_type.starts_with("text/event-stream") {
+            // The stream may stay open while the server works, so read it in the background.
+            let shared = self.shared.clone();
+            tokio::spawn(async move {
+                shared
+                    .forward_events(response, request_id, initialize_id)
+                    .await
+            });
+        } else if content_type.starts_with("application/json") {
+            // The body may only arrive once the server is done, so read it in the background
+            // too, leaving timeouts and cancellation to whoever awaits the response.
+            let shared = self.shared.clone();
+            tokio::spawn(async move {
+                let delivered = match response.bytes().await {
+                    Ok(body) => shared.deliver_body(&body, initialize_id.as_ref()),
+                    Err(e) => Err(Error::Transport(e.to_string())),
+                };
+                if let Err(err) = delivered {
+                    shared.fail(request_id, err);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Provides a stream of messages from every response and the server event stream.
+    /// Messages go to one stream at a time: a later call waits until the earlier stream
+    /// is dropped.
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        let incoming = self.incoming.clone();
+        Box::pin(async_stream::stream! {
+            let mut incoming = incoming.lock().await;
+            while let Some(message) = incoming.recv().await {
+                yield Ok(message);
+            }
+        })
+    }
+
+    /// Stops the server event stream and ends the session, if the server assigned one.
+    async fn close(&self) -> Result<(), Error> {
+        if let Some(task) = self.event_stream.lock().unwrap().take() {
+            task.abort();
+        }
+        if self.session_id().is_none() {
+            return Ok(());
+        }
+        let response = self
+            .with_session(self.http.delete(self.url.clone()))
+            .send()
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            tracing::debug!("Server does not allow clients to end sessions");
+        }
+        *self.shared.session_id.write().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{CancellationToken, Client, RequestOptions, ServerNotification};
+    use crate::client::test::initialize;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode as AxumStatus};
+    use axum::response::sse::{Event, Sse};
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::convert::Infallible;
+    use std::time::Duration;
+
+    /// Requests the test server received, as `(HTTP method, JSON-RPC method, session, version)`.
+    type Log = Arc<Mutex<Vec<(String, String, Option<String>, Option<String>)>>>;
+
+    fn record(log: &Log, http: &str, rpc: &str, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        log.lock().unwrap().push((
+            http.to_string(),
+            rpc.to_string(),
+            header(SESSION_ID_HEADER),
+            header(PROTOCOL_VERSION_HEADER),
+        ));
+    }
+
+    fn sse(messages: Vec<Value>, keep_open: bool) -> Response {
+        let events = futures::stream::iter(
+            messages
+                .into_iter()
+                .map(|m| Ok::<_, Infallible>(Event::default().data(m.to_string()))),
+        );
+        if keep_open {
+            Sse::new(events.chain(futures::stream::pending())).into_response()
+        } else {
+            Sse::new(events).into_response()
+        }
+    }
+
+    async fn handle_post(
+        State(log): State<Log>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> Response {
+        let method = body["method"].as_str().unwrap_or("response").to_string();
+        record(&log, "POST", &method, &headers);
+        let id = body["id"].clone();
+        match method.as_str() {
+            "initialize" => (
+                [(SESSION_ID_HEADER, "session-1")],
+                Json(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "protocolVersion": "2025-06-18",
+                        "capabilities": {},
+                        "serverInfo": { "name": "http-test", "version": "1.0.0" }
+                    }
+                })),
+            )
+                .into_response(),
+            _ if !headers.contains_key(SESSION_ID_HEADER) => {
+                AxumStatus::BAD_REQUEST.into_response()
+            }
+            _ if id.is_null() => AxumStatus::ACCEPTED.into_response(),
+            // Headers go out at once, but the body takes longer than any test waits.
+            "slow" => {
+                let body = futures::stream::once(async move {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    let response = json!({ "jsonrpc": "2.0", "id": id, "result": {} });
+                    Ok::<_, Infallible>(response.to_string())
+                });
+                (
+                    [(axum::http::header::CONTENT_TYPE, "application/json")],
+                    axum::body::Body::from_stream(body),
+                )
+                    .into_response()
+            }
+            // More notifications than any bounded buffer holds, one unreadable event, then
+            // the response.
+            "burst" => {
+                let mut events: Vec<String> = (0..500)
+                    .map(|i| {
+                        json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/message",
+                            "params": { "level": "debug", "data": i }
+                        })
+                        .to_string()
+                    })
+                    .collect();
+                events.push("{not json".to_string());
+                events.push(json!({ "jsonrpc": "2.0", "id": id, "result": {} }).to_string());
+                let events = futures::stream::iter(
+                    events
+                        .into_iter()
+                        .map(|data| Ok::<_, Infallible>(Event::default().data(data))),
+                );
+                Sse::new(events).into_response()
+            }
+            "broken" => (
+                [(axum::http::header::CONTENT_TYPE, "application/json")],
+                "{not json",
+            )
+                .into_response(),
+            // Nothing, not even headers, goes out until the handler is done.
+            "stalled" => {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Json(json!({ "jsonrpc": "2.0", "id": id, "result": {} })).into_response()
+            }
+            // A notification, then the stream ends without the response.
+            "truncated" => sse(
+                vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/message",
+                    "params": { "level": "info", "data": "working" }
+                })],
+                false,
+            ),
+            "tools/list" => sse(
+                vec![
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/message",
+                        "params": { "level": "info", "data": "listing" }
+                    }),
+                    json!({ "jsonrpc": "2.0", "id": id, "result": { "tools": [] } }),
+                ],
+                false,
+            ),
+            _ => Json(json!({ "jsonrpc": "2.0", "id": id, "result": {} })).into_response(),
+        }
+    }
+
+    async fn handle_get(State(log): State<Log>, headers: HeaderMap) -> Response {
+        record(&log, "GET", "", &headers);
+        sse(
+            vec![json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" })],
+            true,
+        )
+    }
+
+    async fn handle_delete(State(log): State<Log>, headers: HeaderMap) -> AxumStatus {
+        record(&log, "DELETE", "", &headers);
+        AxumStatus::OK
+    }
+
+    /// Serves a minimal Streamable HTTP endpoint on a local port.
+    async fn serve() -> (String, Log) {
+        let log = Log::default();
+        let app = Router::new()
+            .route(
+                "/mcp",
+                post(handle_post).get(handle_get).delete(handle_delete),
+            )
+            .with_state(log.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, log)
+    }
+
+    /// Tests a full session: JSON and SSE responses, the session and version headers,
+    /// the server event stream, and ending the session on shutdown.
+    #[tokio::test]
+    async fn test_streamable_http_session() -> Result<(), Error> {
+        let (url, log) = serve().await;
+        let client = Client::new(Arc::new(StreamableHttpTransport::new(&url)?), None, None);
+        let mut notifications = client.notifications();
+
+        initialize(&client).await?;
+        let page = client.list_tools(None).await?;
+        assert!(page.tools.is_empty());
+        client.request("ping", None).await?;
+
+        let changed = tokio::time::timeout(Duration::from_secs(2), async {
+            while let Some(notification) = notifications.next().await {
+                if matches!(notification, ServerNotification::ToolListChanged) {
+                    return true;
+                }
+            }
+            false
+        });
+        assert!(changed
+            .await
+            .expect("Expected a notification on the GET stream"));
+        client.shutdown().await?;
+
+        let log = log.lock().unwrap().clone();
+        let session = Some("session-1".to_string());
+        let version = Some("2025-06-18".to_string());
+        assert_eq!(log[0], ("POST".into(), "initialize".into(), None, None));
+        assert!(log.contains(&(
+            "POST".into(),
+            "notifications/initialized".into(),
+            session.clone(),
+            version.clone()
+        )));
+        assert!(log.contains(&(
+            "POST".into(),
+            "tools/list".into(),
+            session.clone(),
+            version.clone()
+        )));
+        assert!(log.contains(&("GET".into(), "".into(), session.clone(), version.clone())));
+        assert_eq!(
+            log.last(),
+            Some(&("DELETE".into(), "".into(), session, version))
+        );
+        Ok(())
+    }
+
+    /// Tests that a JSON response still being written is subject to the request timeout,
+    /// and that the server is told the request was abandoned.
+    #[tokio::test]
+    async fn test_slow_json_response_times_out() -> Result<(), Error> {
+        let (url, log) = serve().await;
+        let client = Client::new(Arc::new(StreamableHttpTransport::new(&url)?), None, None);
+        initialize(&client).await?;
+
+        let options = RequestOptions::default().timeout(Duration::from_millis(200));
+        let call = client.request_with_options("slow", None, options);
+        let err = tokio::time::timeout(Duration::from_secs(2), call)
+            .await
+            .expect("The request timeout should apply while the body is pending")
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout { .. }), "{:?}", err);
+
+        let cancelled = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let log = log.lock().unwrap().clone();
+                if log.iter().any(|entry| entry.1 == "notifications/cancelled") {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        cancelled.await.expect("Expected notifications/cancelled");
+        Ok(())
+    }
+
+    /// Tests that timeouts and cancellation apply while the server hasn't even sent the
+    /// response headers, and that the server is told about each abandoned request.
+    #[tokio::test]
+    async fn test_stalled_response_times_out_and_cancels() -> Result<(), Error> {
+        let (url, log) = serve().await;
+        let client = Client::new(Arc::new(StreamableHttpTransport::new(&url)?), None, None);
+        initialize(&client).await?;
+
+        let options = RequestOptions::default().timeout(Duration::from_millis(200));
+        let call = client.request_with_options("stalled", None, options);
+        let err = tokio::time::timeout(Duration::from_secs(2), call)
+            .await
+            .expect("The request timeout should apply before the headers arrive")
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout { .. }), "{:?}", err);
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            canceller.cancel("no longer needed");
+        });
+        let options = RequestOptions::default().cancellation(token);
+        let call = client.request_with_options("stalled", None, options);
+        let err = tokio::time::timeout(Duration::from_secs(2), call)
+            .await
+            .expect("Cancellation should apply before the headers arrive")
+            .unwrap_err();
+        assert!(matches!(err, Error::Cancelled { .. }), "{:?}", err);
+
+        let cancelled = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let count = log
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|entry| entry.1 == "notifications/cancelled")
+                    .count();
+                if count == 2 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        cancelled
+            .await
+            .expect("Expected notifications/cancelled for both requests");
+        Ok(())
+    }
+
+    /// Tests that bursts of notifications, unreadable events, unreadable responses and
+    /// event streams ending early leave the client usable, failing at most the request
+    /// they belong to.
+    #[tokio::test]
+    async fn test_bad_messages_do_not_close_the_client() -> Result<(), Error> {
+        let (url, _log) = serve().await;
+        let client = Client::new(Arc::new(StreamableHttpTransport::new(&url)?), None, None);
+        initialize(&client).await?;
+
+        client.request("burst", None).await?;
+        let err = client.request("broken", None).await.unwrap_err();
+        assert!(matches!(err, Error::Protocol { .. }), "{:?}", err);
+        let truncated = client.request("truncated", None);
+        let err = tokio::time::timeout(Duration::from_secs(2), truncated)
+            .await
+            .expect("A stream ending without the response should fail the request")
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol { .. }), "{:?}", err);
+        client.request("ping", None).await?;
+        Ok(())
+    }
+
+    /// Tests that error statuses fail the send instead of leaving the request waiting.
+    #[tokio::test]
+    async fn test_http_error_status() -> Result<(), Error> {
+        let (url, _log) = serve().await;
+        let transport = StreamableHttpTransport::new(&url)?;
+        // No session has been established, so the server rejects anything but initialize.
+        let request = crate::protocol::Request::new("ping", None, RequestId::Number(1));
+        let err = transport.send(Message::Request(request)).await.unwrap_err();
+        assert!(
+            matches!(err, Error::Transport(ref msg) if msg.starts_with("HTTP 400")),
+            "{:?}",
+            err
+        );
+        Ok(())
+    }
+}
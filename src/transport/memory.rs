@@ -0,0 +1,229 @@
+//! An in-process transport pair, for embedding a server in an application or testing a
+//! client and server against each other without spawning anything.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use mcp_client_rs::client::Client;
+//! # use mcp_client_rs::server::{Server, ServerHandler};
+//! # async fn example(handler: Arc<dyn ServerHandler>) -> Result<(), mcp_client_rs::Error> {
+//! let (client_end, server_end) = mcp_client_rs::transport::memory::channel();
+//! let server = Server::new(Arc::new(server_end), handler);
+//! tokio::spawn(async move { server.start().await });
+//! let client = Client::new(Arc::new(client_end), None, None);
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+use crate::{
+    error::Error,
+    transport::{Message, Transport},
+};
+
+/// One end of an in-memory connection created by [`channel`].
+pub struct MemoryTransport {
+    /// Delivers messages to the other end. Dropped on close, which ends the other end's stream.
+    outgoing: Mutex<Option<mpsc::UnboundedSender<Message>>>,
+    /// Messages from the other end, kept until read.
+    incoming: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>>,
+}
+
+/// Creates two connected transports: whatever one end sends, the other receives.
+///
+/// Closing or dropping either end ends the other end's `receive` stream.
+pub fn channel() -> (MemoryTransport, MemoryTransport) {
+    let (a_to_b, b_from_a) = mpsc::unbounded_channel();
+    let (b_to_a, a_from_b) = mpsc::unbounded_channel();
+    (
+        MemoryTransport::new(a_to_b, a_from_b),
+        MemoryTransport::new(b_to_a, b_from_a),
+    )
+}
+
+impl MemoryTransport {
+    fn new(
+        outgoing: mpsc::UnboundedSender<Message>,
+        incoming: mpsc::UnboundedReceiver<Message>,
+    ) -> Self {
+        Self {
+            outgoing: Mutex::new(Some(outgoing)),
+            incoming: Arc::new(tokio::sync::Mutex::new(incoming)),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    /// Hands a message to the other end.
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        let outgoing = self.outgoing.lock().unwrap().clone();
+        let Some(outgoing) = outgoing else {
+            return Err(Error::Transport("Transport closed".to_string()));
+        };
+        outgoing
+            .send(message)
+            .map_err(|_| Error::Transport("The other end disconnected".to_string()))
+    }
+
+    /// Provides a stream of the messages the other end sends, including those sent before
+    /// the call. Messages go to one stream at a time: a later call waits until the earlier
+    /// stream is dropped.
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        let incoming = self.incoming.clone();
+        Box::pin(async_stream::stream! {
+            let mut incoming = incoming.lock().await;
+            while let Some(message) = incoming.recv().await {
+                yield Ok(message);
+            }
+        })
+    }
+
+    /// Disconnects, ending the other end's stream once it has read everything sent so far.
+    async fn close(&self) -> Result<(), Error> {
+        self.outgoing.lock().unwrap().take();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Notification, Request, RequestId};
+    use crate::server::{Server, ServerHandler};
+    use crate::types::{ClientCapabilities, Implementation, ServerCapabilities, ToolsCapability};
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Server offering a single `echo` tool.
+    struct EchoServer;
+
+    #[async_trait]
+    impl ServerHandler for EchoServer {
+        async fn initialize(
+            &self,
+            implementation: Implementation,
+            _capabilities: ClientCapabilities,
+        ) -> Result<ServerCapabilities, Error> {
+            assert_eq!(implementation.name, "in-process");
+            Ok(ServerCapabilities {
+                tools: Some(ToolsCapability { list_changed: None }),
+                ..Default::default()
+            })
+        }
+
+        async fn shutdown(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn handle_method(
+            &self,
+            method: &str,
+            params: Option<serde_json::Value>,
+        ) -> Result<serde_json::Value, Error> {
+            let params = params.unwrap_or_default();
+            match method {
+                "tools/list" => Ok(json!({
+                    "tools": [{ "name": "echo", "inputSchema": { "type": "object" } }]
+                })),
+                "tools/call" => Ok(json!({
+                    "content": [{ "type": "text", "text": params["arguments"]["text"] }]
+                })),
+                _ => Err(Error::protocol(
+                    crate::error::ErrorCode::MethodNotFound,
+                    method,
+                )),
+            }
+        }
+    }
+
+    /// Tests that messages sent before the first `receive` are kept, and that closing
+    /// one end ends the other end's stream.
+    #[tokio::test]
+    async fn test_channel_buffering_and_close() -> Result<(), Error> {
+        let (a, b) = channel();
+        a.send(Message::Notification(Notification::new("first", None)))
+            .await?;
+
+        let mut messages = b.receive();
+        match messages.next().await {
+            Some(Ok(Message::Notification(notification))) => {
+                assert_eq!(notification.method, "first")
+            }
+            other => panic!("Expected the buffered notification, got {:?}", other),
+        }
+
+        a.close().await?;
+        assert!(messages.next().await.is_none());
+        assert!(matches!(
+            a.send(Message::Notification(Notification::new("late", None)))
+                .await,
+            Err(Error::Transport(_))
+        ));
+        Ok(())
+    }
+
+    /// Tests that a burst of messages larger than any buffer is delivered in full.
+    #[tokio::test]
+    async fn test_channel_burst() -> Result<(), Error> {
+        let (a, b) = channel();
+        for i in 0..1000 {
+            let params = json!({ "progress": i });
+            a.send(Message::Notification(Notification::new(
+                "progress",
+                Some(params),
+            )))
+            .await?;
+        }
+        a.close().await?;
+        assert_eq!(b.receive().count().await, 1000);
+        Ok(())
+    }
+
+    /// Tests a `Server` answering requests sent through the other end.
+    #[tokio::test]
+    async fn test_server_in_process() -> Result<(), Error> {
+        let (client_end, server_end) = channel();
+        let server = Server::new(Arc::new(server_end), Arc::new(EchoServer));
+        let server = tokio::spawn(async move { server.start().await });
+        let mut messages = client_end.receive();
+
+        let initialize = json!({
+            "implementation": { "name": "in-process", "version": "0.1.0" },
+            "capabilities": {}
+        });
+        let initialize = Request::new("initialize", Some(initialize), RequestId::Number(1));
+        client_end.send(Message::Request(initialize)).await?;
+        match messages.next().await {
+            Some(Ok(Message::Response(response))) => {
+                assert!(response.result.unwrap()["capabilities"]["tools"].is_object())
+            }
+            other => panic!("Expected the initialize response, got {:?}", other),
+        }
+        let initialized = Notification::new("initialized", None);
+        client_end.send(Message::Notification(initialized)).await?;
+
+        let arguments = json!({ "name": "echo", "arguments": { "text": "hi" } });
+        let call = Request::new("tools/call", Some(arguments), RequestId::Number(2));
+        client_end.send(Message::Request(call)).await?;
+        match messages.next().await {
+            Some(Ok(Message::Response(response))) => {
+                assert_eq!(response.result.unwrap()["content"][0]["text"], "hi")
+            }
+            other => panic!("Expected the tools/call response, got {:?}", other),
+        }
+
+        client_end.close().await?;
+        let stopped = tokio::time::timeout(Duration::from_secs(1), server)
+            .await
+            .expect("Server should stop once the client disconnects");
+        assert!(stopped.unwrap().is_ok());
+        Ok(())
+    }
+}
@@ -17,16 +17,6 @@ pub enum Message {
     Notification(Notification),
 }
 
-impl Message {
-    fn message_type(&self) -> &'static str {
-        match self {
-            Message::Request(_) => "request",
-            Message::Response(_) => "response",
-            Message::Notification(_) => "notification",
-        }
-    }
-}
-
 // Custom visitor for more efficient deserialization
 struct MessageVisitor;
 
@@ -109,9 +99,6 @@ impl Serialize for Message {
     {
         let mut map = serializer.serialize_map(None)?;
 
-        // Always add type field for our protocol
-        // map.serialize_entry("type", self.message_type())?;
-
         // Add message-specific fields
         match self {
             Message::Request(req) => {
@@ -158,4 +145,12 @@ pub trait Transport: Send + Sync + 'static {
     async fn close(&self) -> Result<(), Error>;
 }
 
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "http")]
+pub mod sse;
+pub mod memory;
+pub mod socket;
 pub mod stdio;
+#[cfg(feature = "websocket")]
+pub mod websocket;
@@ -0,0 +1,319 @@
+//! Transports over TCP and Unix domain sockets, for talking to long-running servers.
+//!
+//! Both use the newline-delimited framing of [`StdioTransport`]: one JSON-RPC message
+//! per line. The listeners accept any number of clients, each on its own transport.
+
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{tcp, ToSocketAddrs};
+
+use crate::{
+    error::Error,
+    transport::{stdio::StdioTransport, Transport},
+};
+
+/// A transport over a TCP connection.
+pub type TcpTransport = StdioTransport<tcp::OwnedWriteHalf>;
+
+impl StdioTransport<tcp::OwnedWriteHalf> {
+    /// Connects to a server listening on `addr`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Io` if the connection can't be established.
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+        Self::from_tcp(stream)
+    }
+
+    fn from_tcp(stream: tokio::net::TcpStream) -> Result<Self, Error> {
+        stream
+            .set_nodelay(true)
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        let (read, write) = stream.into_split();
+        Self::with_streams(read, write)
+    }
+}
+
+/// Trait for accepting client connections, each as its own transport.
+#[async_trait]
+pub trait Listener: Send + Sync + 'static {
+    /// Waits for the next client to connect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Transport` for failures worth retrying, such as a client that
+    /// disconnected before it was accepted or running out of file descriptors, and any
+    /// other error if the listener can't accept anymore.
+    async fn accept(&self) -> Result<Arc<dyn Transport>, Error>;
+}
+
+/// Converts a failed `accept` into an `Error::Transport` unless the listening socket
+/// itself is unusable.
+fn accept_error(err: std::io::Error) -> Error {
+    match err.kind() {
+        std::io::ErrorKind::InvalidInput | std::io::ErrorKind::Unsupported => {
+            Error::Io(err.to_string())
+        }
+        _ => Error::Transport(format!("Failed to accept a connection: {}", err)),
+    }
+}
+
+/// Accepts MCP clients over TCP.
+pub struct TcpTransportListener {
+    listener: tokio::net::TcpListener,
+}
+
+impl TcpTransportListener {
+    /// Listens on `addr`. Bind to port 0 to let the OS pick a free port.
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        Ok(Self { listener })
+    }
+
+    /// The address the listener is bound to.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.listener.local_addr()?)
+    }
+}
+
+#[async_trait]
+impl Listener for TcpTransportListener {
+    async fn accept(&self) -> Result<Arc<dyn Transport>, Error> {
+        let (stream, peer) = self.listener.accept().await.map_err(accept_error)?;
+        tracing::debug!(%peer, "Accepted TCP client");
+        Ok(Arc::new(TcpTransport::from_tcp(stream)?))
+    }
+}
+
+#[cfg(unix)]
+pub use self::unix::{UnixSocketTransport, UnixSocketTransportListener};
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use tokio::net::unix;
+
+    /// A transport over a Unix domain socket.
+    pub type UnixSocketTransport = StdioTransport<unix::OwnedWriteHalf>;
+
+    impl StdioTransport<unix::OwnedWriteHalf> {
+        /// Connects to a server listening on the socket at `path`.
+        ///
+        /// # Errors
+        ///
+        /// Returns an `Error::Io` if the connection can't be established.
+        pub async fn connect(path: impl AsRef<Path>) -> Result<Self, Error> {
+            let stream = tokio::net::UnixStream::connect(path).await?;
+            let (read, write) = stream.into_split();
+            Self::with_streams(read, write)
+        }
+    }
+
+    /// Accepts MCP clients over a Unix domain socket.
+    pub struct UnixSocketTransportListener {
+        listener: tokio::net::UnixListener,
+        path: PathBuf,
+    }
+
+    impl UnixSocketTransportListener {
+        /// Listens on a new socket at `path`, which must not exist yet.
+        pub fn bind(path: impl AsRef<Path>) -> Result<Self, Error> {
+            let path = path.as_ref().to_path_buf();
+            let listener = tokio::net::UnixListener::bind(&path)?;
+            Ok(Self { listener, path })
+        }
+
+        /// The path of the socket.
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    #[async_trait]
+    impl Listener for UnixSocketTransportListener {
+        async fn accept(&self) -> Result<Arc<dyn Transport>, Error> {
+            let (stream, _) = self.listener.accept().await.map_err(accept_error)?;
+            tracing::debug!(path = %self.path.display(), "Accepted Unix socket client");
+            let (read, write) = stream.into_split();
+            Ok(Arc::new(UnixSocketTransport::with_streams(read, write)?))
+        }
+    }
+
+    impl Drop for UnixSocketTransportListener {
+        /// Removes the socket file, so the path can be bound again.
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Notification, Request, RequestId};
+    use crate::server::{Server, ServerHandler};
+    use crate::transport::Message;
+    use crate::types::{ClientCapabilities, Implementation, ServerCapabilities};
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::time::Duration;
+
+    /// Handler answering every method with its parameters.
+    struct EchoHandler;
+
+    #[async_trait]
+    impl ServerHandler for EchoHandler {
+        async fn initialize(
+            &self,
+            _implementation: Implementation,
+            _capabilities: ClientCapabilities,
+        ) -> Result<ServerCapabilities, Error> {
+            Ok(ServerCapabilities::default())
+        }
+
+        async fn shutdown(&self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn handle_method(
+            &self,
+            _method: &str,
+            params: Option<serde_json::Value>,
+        ) -> Result<serde_json::Value, Error> {
+            Ok(params.unwrap_or_default())
+        }
+    }
+
+    /// Initializes a session over `transport` and returns what `echo` answers with.
+    async fn echo(transport: impl Transport, text: &str) -> serde_json::Value {
+        let mut messages = transport.receive();
+        let initialize = json!({
+            "implementation": { "name": "test", "version": "0.1.0" },
+            "capabilities": {}
+        });
+        let initialize = Request::new("initialize", Some(initialize), RequestId::Number(1));
+        let echo = Request::new("echo", Some(json!({ "text": text })), RequestId::Number(2));
+
+        transport.send(Message::Request(initialize)).await.unwrap();
+        let next = tokio::time::timeout(Duration::from_secs(1), messages.next());
+        assert!(matches!(
+            next.await.unwrap(),
+            Some(Ok(Message::Response(_)))
+        ));
+        let initialized = Notification::new("initialized", None);
+        transport
+            .send(Message::Notification(initialized))
+            .await
+            .unwrap();
+        transport.send(Message::Request(echo)).await.unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(1), messages.next());
+        match next.await.unwrap() {
+            Some(Ok(Message::Response(response))) => response.result.unwrap(),
+            other => panic!("Expected a response, got {:?}", other),
+        }
+    }
+
+    /// Tests that one TCP listener serves several clients, each with its own session.
+    #[tokio::test]
+    async fn test_tcp_many_clients() -> Result<(), Error> {
+        let listener = TcpTransportListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(Server::serve(listener, Arc::new(EchoHandler)));
+
+        let first = TcpTransport::connect(addr).await?;
+        let second = TcpTransport::connect(addr).await?;
+        let (a, b) = tokio::join!(echo(first, "a"), echo(second, "b"));
+        assert_eq!(a, json!({ "text": "a" }));
+        assert_eq!(b, json!({ "text": "b" }));
+        Ok(())
+    }
+
+    /// Listener failing transiently twice, then accepting one client, then failing for good.
+    struct FlakyListener {
+        attempts: std::sync::Mutex<usize>,
+        client: std::sync::Mutex<Option<crate::transport::memory::MemoryTransport>>,
+    }
+
+    #[async_trait]
+    impl Listener for FlakyListener {
+        async fn accept(&self) -> Result<Arc<dyn Transport>, Error> {
+            let attempt = {
+                let mut attempts = self.attempts.lock().unwrap();
+                *attempts += 1;
+                *attempts
+            };
+            match attempt {
+                1 | 2 => Err(Error::Transport("Too many open files".to_string())),
+                3 => Ok(Arc::new(self.client.lock().unwrap().take().unwrap())),
+                _ => Err(Error::Io("Listener closed".to_string())),
+            }
+        }
+    }
+
+    /// Tests that `serve` keeps accepting after transient failures and stops on others.
+    #[tokio::test]
+    async fn test_serve_retries_transient_accept_errors() -> Result<(), Error> {
+        let (client_end, server_end) = crate::transport::memory::channel();
+        let listener = FlakyListener {
+            attempts: std::sync::Mutex::new(0),
+            client: std::sync::Mutex::new(Some(server_end)),
+        };
+        let served = Server::serve(listener, Arc::new(EchoHandler));
+        let served = tokio::time::timeout(Duration::from_secs(1), served).await;
+        assert!(matches!(served, Ok(Err(Error::Io(_)))));
+
+        assert_eq!(echo(client_end, "hi").await, json!({ "text": "hi" }));
+        Ok(())
+    }
+
+    /// Tests that a burst of lines larger than any buffer is delivered in full, skipping a
+    /// line that isn't a message.
+    #[tokio::test]
+    async fn test_tcp_burst() -> Result<(), Error> {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let transport = TcpTransport::connect(listener.local_addr()?).await?;
+        let (mut stream, _) = listener.accept().await?;
+        let mut lines = String::new();
+        for i in 0..1000 {
+            let params = json!({ "progress": i });
+            let notification = Message::Notification(Notification::new("progress", Some(params)));
+            lines.push_str(&serde_json::to_string(&notification)?);
+            lines.push('\n');
+            if i == 500 {
+                lines.push_str("not a message\n");
+            }
+        }
+        stream.write_all(lines.as_bytes()).await?;
+        drop(stream);
+
+        let messages: Vec<_> = transport.receive().collect().await;
+        assert_eq!(messages.iter().filter(|m| m.is_ok()).count(), 1000);
+        assert!(matches!(messages.last(), Some(Err(_))));
+        Ok(())
+    }
+
+    /// Tests a session over a Unix domain socket, and that the socket file is removed
+    /// with the listener.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket() -> Result<(), Error> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("mcp.sock");
+        let listener = UnixSocketTransportListener::bind(&path)?;
+        let server = tokio::spawn(Server::serve(listener, Arc::new(EchoHandler)));
+
+        let transport = UnixSocketTransport::connect(&path).await?;
+        assert_eq!(echo(transport, "hi").await, json!({ "text": "hi" }));
+
+        server.abort();
+        let _ = server.await;
+        assert!(!path.exists());
+        Ok(())
+    }
+}
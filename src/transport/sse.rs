@@ -0,0 +1,293 @@
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{Stream, StreamExt};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::Url;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::{
+    error::Error,
+    transport::{Message, Transport},
+};
+
+/// A transport for servers speaking the HTTP+SSE protocol of the 2024-11-05 revision.
+///
+/// The server pushes every message over a long-lived GET event stream, which starts by
+/// announcing the URL to POST client messages to in an `endpoint` event. Unreadable
+/// events are logged and skipped; the `receive` stream only fails when the event stream
+/// breaks or ends.
+pub struct SseTransport {
+    http: reqwest::Client,
+    /// Where to POST messages, as announced by the server.
+    endpoint: Url,
+    /// Messages from the event stream, kept until read.
+    incoming: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Result<Message, Error>>>>,
+    /// The task reading the event stream.
+    reader: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SseTransport {
+    /// Opens the event stream at `url`, e.g. `https://example.com/sse`, and waits for the
+    /// server to announce its message endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Transport` if the stream can't be opened, or ends or announces an
+    /// endpoint on another origin before a usable endpoint arrives.
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        Self::connect_with_client(url, reqwest::Client::new()).await
+    }
+
+    /// Like [`SseTransport::connect`], but using a preconfigured HTTP client, e.g. one
+    /// with default headers for authentication.
+    pub async fn connect_with_client(url: &str, http: reqwest::Client) -> Result<Self, Error> {
+        let url = Url::parse(url).map_err(|e| Error::Transport(format!("Invalid URL: {}", e)))?;
+        let response = http
+            .get(url.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(Error::Transport(format!(
+                "HTTP {} opening event stream",
+                response.status()
+            )));
+        }
+
+        let mut events = response.bytes_stream().eventsource();
+        let endpoint = loop {
+            match events.next().await {
+                Some(Ok(event)) if event.event == "endpoint" => {
+                    break url.join(event.data.trim()).map_err(|e| {
+                        Error::Transport(format!("Invalid endpoint '{}': {}", event.data, e))
+                    })?;
+                }
+                Some(Ok(event)) => {
+                    tracing::debug!(event = %event.event, "Skipping event before endpoint");
+                }
+                Some(Err(e)) => return Err(Error::Transport(e.to_string())),
+                None => {
+                    return Err(Error::Transport(
+                        "Event stream ended before the server announced an endpoint".to_string(),
+                    ))
+                }
+            }
+        };
+        // Never post messages, and any credentials, to a host the user didn't connect to.
+        if endpoint.origin() != url.origin() {
+            return Err(Error::Transport(format!(
+                "Endpoint '{}' is not on the origin of '{}'",
+                endpoint, url
+            )));
+        }
+        tracing::debug!(%endpoint, "Server announced message endpoint");
+
+        let (sender, incoming) = mpsc::unbounded_channel();
+        let reader = tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        let _ = sender.send(Err(Error::Transport(e.to_string())));
+                        return;
+                    }
+                };
+                if event.event != "message" {
+                    tracing::debug!(event = %event.event, "Ignoring event");
+                    continue;
+                }
+                match serde_json::from_str::<Message>(&event.data) {
+                    Ok(message) => {
+                        let _ = sender.send(Ok(message));
+                    }
+                    Err(err) => {
+                        tracing::warn!(data = %event.data, %err, "Skipping unreadable event");
+                    }
+                }
+            }
+            tracing::debug!("Event stream ended");
+            let _ = sender.send(Err(Error::Transport("Event stream closed".to_string())));
+        });
+
+        Ok(Self {
+            http,
+            endpoint,
+            incoming: Arc::new(tokio::sync::Mutex::new(incoming)),
+            reader: Mutex::new(Some(reader)),
+        })
+    }
+
+    /// The URL messages are posted to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+}
+
+#[async_trait]
+impl Transport for SseTransport {
+    /// POSTs a message to the announced endpoint. Replies arrive on the event stream.
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        let response = self
+            .http
+            .post(self.endpoint.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&message)?)
+            .send()
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Transport(format!("HTTP {}: {}", status, body)));
+        }
+        Ok(())
+    }
+
+    /// Provides a stream of the messages the server sends over the event stream.
+    /// Messages go to one stream at a time: a later call waits until the earlier stream
+    /// is dropped.
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        let incoming = self.incoming.clone();
+        Box::pin(async_stream::stream! {
+            let mut incoming = incoming.lock().await;
+            while let Some(message) = incoming.recv().await {
+                yield message;
+            }
+        })
+    }
+
+    /// Closes the event stream, which ends the session on the server.
+    async fn close(&self) -> Result<(), Error> {
+        if let Some(reader) = self.reader.lock().unwrap().take() {
+            reader.abort();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::client::test::initialize;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::response::sse::{Event, Sse};
+    use axum::response::IntoResponse;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    /// Messages the test server pushes onto the open event stream.
+    type Outbox = Arc<Mutex<Option<mpsc::UnboundedSender<Value>>>>;
+
+    async fn handle_sse(State(outbox): State<Outbox>) -> impl IntoResponse {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *outbox.lock().unwrap() = Some(tx);
+        let endpoint = futures::stream::once(async {
+            Ok::<_, Infallible>(
+                Event::default()
+                    .event("endpoint")
+                    .data("/messages?session_id=s1"),
+            )
+        });
+        let messages = futures::stream::unfold(rx, |mut rx| async move {
+            let message = rx.recv().await?;
+            Some((
+                Ok(Event::default().event("message").data(message.to_string())),
+                rx,
+            ))
+        });
+        Sse::new(endpoint.chain(messages))
+    }
+
+    async fn handle_message(State(outbox): State<Outbox>, Json(body): Json<Value>) -> StatusCode {
+        let result = match body["method"].as_str() {
+            Some("initialize") => json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "serverInfo": { "name": "sse-test", "version": "1.0.0" }
+            }),
+            _ if body.get("id").is_none() => return StatusCode::ACCEPTED,
+            _ => json!({}),
+        };
+        let response = json!({ "jsonrpc": "2.0", "id": body["id"], "result": result });
+        let outbox = outbox.lock().unwrap();
+        let outbox = outbox.as_ref().unwrap();
+        if body["method"] == "burst" {
+            // More notifications than any bounded buffer holds, then an unreadable message.
+            for i in 0..500 {
+                let log = json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/message",
+                    "params": { "level": "debug", "data": i }
+                });
+                outbox.send(log).unwrap();
+            }
+            outbox.send(json!("not a message")).unwrap();
+        }
+        outbox.send(response).unwrap();
+        StatusCode::ACCEPTED
+    }
+
+    async fn serve(endpoint_route: &'static str) -> String {
+        let app = Router::new()
+            .route("/sse", get(handle_sse))
+            .route(endpoint_route, post(handle_message))
+            .with_state(Outbox::default());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/sse", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    /// Tests endpoint discovery and a request/response round trip over the event stream.
+    #[tokio::test]
+    async fn test_sse_round_trip() -> Result<(), Error> {
+        let url = serve("/messages").await;
+        let transport = SseTransport::connect(&url).await?;
+        assert_eq!(transport.endpoint().path(), "/messages");
+        assert_eq!(transport.endpoint().query(), Some("session_id=s1"));
+
+        let client = Client::new(Arc::new(transport), None, None);
+        let result = initialize(&client).await?;
+        assert_eq!(result.server_info.name, "sse-test");
+        client.request("ping", None).await?;
+        client.shutdown().await
+    }
+
+    /// Tests that bursts of notifications and unreadable messages leave the client usable.
+    #[tokio::test]
+    async fn test_sse_bad_messages_do_not_close_the_client() -> Result<(), Error> {
+        let url = serve("/messages").await;
+        let client = Client::new(Arc::new(SseTransport::connect(&url).await?), None, None);
+        client.request("burst", None).await?;
+        client.request("ping", None).await?;
+        Ok(())
+    }
+
+    /// Tests that a failed POST is reported instead of leaving the request waiting.
+    #[tokio::test]
+    async fn test_sse_post_error() -> Result<(), Error> {
+        let url = serve("/elsewhere").await;
+        let transport = SseTransport::connect(&url).await?;
+        let notification = crate::protocol::Notification::new("notifications/initialized", None);
+        let err = transport
+            .send(Message::Notification(notification))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Transport(ref msg) if msg.starts_with("HTTP 404")),
+            "{:?}",
+            err
+        );
+        Ok(())
+    }
+}
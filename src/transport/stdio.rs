@@ -1,13 +1,14 @@
 use async_trait::async_trait;
-use futures::{Stream, StreamExt};
+use futures::Stream;
 use std::pin::Pin;
+use std::sync::Arc;
 use tokio::{
     io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
-    sync::broadcast,
+    sync::mpsc,
 };
 
 use crate::{
-    error::{Error, ErrorCode},
+    error::Error,
     transport::{Message, Transport},
 };
 
@@ -15,10 +16,8 @@ use crate::{
 pub struct StdioTransport<W> {
     /// A mutex-protected writer for sending messages.
     writer: tokio::sync::Mutex<W>,
-    /// A broadcast receiver for incoming messages read from the stream.
-    receiver: broadcast::Receiver<Result<Message, Error>>,
-    // Keep sender in scope to avoid dropping.
-    _sender: broadcast::Sender<Result<Message, Error>>,
+    /// Messages read from the stream, kept until read.
+    incoming: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Result<Message, Error>>>>,
 }
 
 impl<W> StdioTransport<W>
@@ -34,10 +33,9 @@ where
     where
         R: AsyncRead + Unpin + Send + 'static,
     {
-        let (sender, receiver) = broadcast::channel(100);
+        let (sender, incoming) = mpsc::unbounded_channel();
         let writer = tokio::sync::Mutex::new(write);
 
-        let sender_clone = sender.clone();
         tokio::spawn(async move {
             let mut reader = BufReader::new(read);
             let mut line = String::new();
@@ -49,7 +47,7 @@ where
                         tracing::debug!(
                             "EOF reached, send an EOF error so the stream ends gracefully"
                         );
-                        let _ = sender_clone.send(Err(Error::Other("EOF".to_string())));
+                        let _ = sender.send(Err(Error::Other("EOF".to_string())));
                         break;
                     }
                     Ok(_) => {
@@ -57,15 +55,19 @@ where
                         if trimmed.is_empty() {
                             continue;
                         }
-                        let message = match serde_json::from_str::<Message>(trimmed) {
-                            Ok(m) => Ok(m),
-                            Err(err) => Err(Error::Serialization(err.to_string())),
-                        };
-
-                        let _ = sender_clone.send(message);
+                        match serde_json::from_str::<Message>(trimmed) {
+                            Ok(message) => {
+                                if sender.send(Ok(message)).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(err) => {
+                                tracing::warn!(line = %trimmed, %err, "Skipping unreadable line");
+                            }
+                        }
                     }
                     Err(err) => {
-                        let _ = sender_clone.send(Err(Error::Io(err.to_string())));
+                        let _ = sender.send(Err(Error::Io(err.to_string())));
                         break;
                     }
                 }
@@ -74,8 +76,7 @@ where
 
         Ok(StdioTransport {
             writer,
-            receiver,
-            _sender: sender,
+            incoming: Arc::new(tokio::sync::Mutex::new(incoming)),
         })
     }
 }
@@ -103,14 +104,16 @@ where
     }
 
     /// Provides a stream of incoming messages read from the stdin or other input stream.
+    /// Lines that aren't valid messages are logged and skipped. Messages go to one stream
+    /// at a time: a later call waits until the earlier stream is dropped.
     fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
-        let rx = self.receiver.resubscribe();
-        Box::pin(futures::stream::unfold(rx, |mut rx| async move {
-            match rx.recv().await {
-                Ok(msg) => Some((msg, rx)),
-                Err(_) => None,
+        let incoming = self.incoming.clone();
+        Box::pin(async_stream::stream! {
+            let mut incoming = incoming.lock().await;
+            while let Some(message) = incoming.recv().await {
+                yield message;
             }
-        }))
+        })
     }
 
     async fn close(&self) -> Result<(), Error> {
@@ -0,0 +1,392 @@
+use async_trait::async_trait;
+use futures::stream::SplitSink;
+use futures::{SinkExt, Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::Message as Frame;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::{
+    error::Error,
+    transport::{Message, Transport},
+};
+
+/// How often the connection is pinged unless configured otherwise.
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A transport exchanging one JSON-RPC message per WebSocket text frame.
+///
+/// The connection is pinged periodically, and considered dead if nothing arrives for two
+/// ping intervals. Frames that aren't valid messages are logged and skipped. The `receive`
+/// stream ends when the server closes the connection, with a final error first if it
+/// closed abnormally. Dropping the transport closes the connection.
+pub struct WebSocketTransport {
+    sink: Arc<Mutex<SplitSink<Socket, Frame>>>,
+    /// Messages read from the socket, kept until read.
+    incoming: Arc<Mutex<mpsc::UnboundedReceiver<Result<Message, Error>>>>,
+    /// The task reading the socket and sending keepalive pings.
+    reader: JoinHandle<()>,
+}
+
+/// Builder for a [`WebSocketTransport`], for adding headers or tuning the keepalive.
+pub struct WebSocketBuilder {
+    url: String,
+    headers: Vec<(String, String)>,
+    ping_interval: Option<Duration>,
+}
+
+impl WebSocketBuilder {
+    /// Adds a header to the opening handshake, e.g. `Authorization`.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        tracing::trace!(%name, "Adding header to WebSocketBuilder");
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets how often the connection is pinged, or disables keepalive with `None`.
+    pub fn ping_interval(mut self, interval: Option<Duration>) -> Self {
+        tracing::trace!(?interval, "Setting ping interval for WebSocketBuilder");
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Opens the connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Transport` if a header is invalid or the handshake fails.
+    pub async fn connect(self) -> Result<WebSocketTransport, Error> {
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::Transport(format!("Invalid header name '{}': {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| Error::Transport(format!("Invalid value for '{}': {}", name, e)))?;
+            request.headers_mut().append(name, value);
+        }
+
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        tracing::debug!(url = %self.url, "WebSocket connected");
+        Ok(WebSocketTransport::with_socket(socket, self.ping_interval))
+    }
+}
+
+impl WebSocketTransport {
+    /// Connects to `url` (`ws://` or `wss://`) with the default keepalive.
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        Self::builder(url).connect().await
+    }
+
+    /// Starts building a connection to `url`.
+    pub fn builder(url: &str) -> WebSocketBuilder {
+        WebSocketBuilder {
+            url: url.to_string(),
+            headers: Vec::new(),
+            ping_interval: Some(DEFAULT_PING_INTERVAL),
+        }
+    }
+
+    fn with_socket(socket: Socket, ping_interval: Option<Duration>) -> Self {
+        let (sink, mut stream) = socket.split();
+        let sink = Arc::new(Mutex::new(sink));
+        let (sender, incoming) = mpsc::unbounded_channel();
+
+        let pinger = sink.clone();
+        let reader = tokio::spawn(async move {
+            let mut last_seen = Instant::now();
+            let mut ticker = ping_interval.map(tokio::time::interval);
+            loop {
+                let frame = tokio::select! {
+                    frame = stream.next() => frame,
+                    _ = async { ticker.as_mut().unwrap().tick().await }, if ticker.is_some() => {
+                        let interval = ping_interval.unwrap_or_default();
+                        if last_seen.elapsed() > interval * 2 {
+                            tracing::warn!(?interval, "WebSocket keepalive timed out");
+                            let _ = sender.send(Err(Error::Transport(
+                                "WebSocket keepalive timed out".to_string(),
+                            )));
+                            break;
+                        }
+                        let _ = pinger.lock().await.send(Frame::Ping(Default::default())).await;
+                        continue;
+                    }
+                };
+                last_seen = Instant::now();
+
+                let message = match frame {
+                    Some(Ok(Frame::Text(text))) => match serde_json::from_str::<Message>(&text) {
+                        Ok(message) => message,
+                        Err(err) => {
+                            tracing::warn!(%text, %err, "Skipping unreadable WebSocket frame");
+                            continue;
+                        }
+                    },
+                    Some(Ok(Frame::Close(close))) => {
+                        let abnormal = close
+                            .as_ref()
+                            .filter(|c| !matches!(c.code, CloseCode::Normal | CloseCode::Away));
+                        if let Some(close) = abnormal {
+                            let _ = sender.send(Err(Error::Transport(format!(
+                                "WebSocket closed: {} {}",
+                                close.code, close.reason
+                            ))));
+                        }
+                        tracing::debug!(?close, "WebSocket closed by server");
+                        break;
+                    }
+                    Some(Ok(Frame::Binary(_))) => {
+                        tracing::warn!("Ignoring binary WebSocket frame");
+                        continue;
+                    }
+                    // Pings are answered by tungstenite itself.
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        let _ = sender.send(Err(Error::Transport(e.to_string())));
+                        break;
+                    }
+                    None => break,
+                };
+                let _ = sender.send(Ok(message));
+            }
+            // Dropping the sender ends every `receive` stream.
+            tracing::debug!("WebSocket reader terminated");
+        });
+
+        Self {
+            sink,
+            incoming: Arc::new(Mutex::new(incoming)),
+            reader,
+        }
+    }
+}
+
+impl Drop for WebSocketTransport {
+    /// Stops the reader and keepalive, and sends a close frame in the background.
+    fn drop(&mut self) {
+        self.reader.abort();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let sink = self.sink.clone();
+            runtime.spawn(async move {
+                let _ = sink.lock().await.close().await;
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    /// Sends a message as a single text frame.
+    async fn send(&self, message: Message) -> Result<(), Error> {
+        let json = serde_json::to_string(&message)?;
+        self.sink
+            .lock()
+            .await
+            .send(Frame::text(json))
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))
+    }
+
+    /// Provides a stream of incoming messages, ending when the connection closes.
+    /// Messages go to one stream at a time: a later call waits until the earlier stream
+    /// is dropped.
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = Result<Message, Error>> + Send>> {
+        let incoming = self.incoming.clone();
+        Box::pin(async_stream::stream! {
+            let mut incoming = incoming.lock().await;
+            while let Some(message) = incoming.recv().await {
+                yield message;
+            }
+        })
+    }
+
+    /// Sends a close frame; the stream ends once the server acknowledges it.
+    async fn close(&self) -> Result<(), Error> {
+        let mut sink = self.sink.lock().await;
+        match sink.close().await {
+            Ok(()) => Ok(()),
+            Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed) => Ok(()),
+            Err(e) => Err(Error::Transport(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use serde_json::json;
+    use std::future::Future;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+    /// Accepts one WebSocket connection on a local port and hands it to `serve`, along
+    /// with the `Authorization` header of the handshake.
+    async fn serve<F, Fut>(serve: F) -> String
+    where
+        F: FnOnce(WebSocketStream<TcpStream>, Option<String>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut authorization = None;
+            #[allow(clippy::result_large_err)]
+            let socket = tokio_tungstenite::accept_hdr_async(
+                stream,
+                |request: &Request, response: Response| {
+                    authorization = request
+                        .headers()
+                        .get("authorization")
+                        .map(|v| v.to_str().unwrap().to_string());
+                    Ok(response)
+                },
+            )
+            .await
+            .unwrap();
+            serve(socket, authorization).await;
+        });
+        url
+    }
+
+    fn close(code: CloseCode) -> Frame {
+        Frame::Close(Some(CloseFrame {
+            code,
+            reason: "bye".into(),
+        }))
+    }
+
+    /// Tests requests over text frames, handshake headers, that unreadable frames are
+    /// skipped, and that a normal close ends the receive stream.
+    #[tokio::test]
+    async fn test_websocket_round_trip() -> Result<(), Error> {
+        let url = serve(|mut socket, authorization| async move {
+            while let Some(Ok(frame)) = socket.next().await {
+                // Keepalive pings show up here too; tungstenite answers them itself.
+                let Frame::Text(text) = frame else { continue };
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                socket.send(Frame::text("not a message")).await.unwrap();
+                let result = json!({ "authorization": authorization });
+                let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+                socket
+                    .send(Frame::text(response.to_string()))
+                    .await
+                    .unwrap();
+                if request["method"] == "bye" {
+                    socket.send(close(CloseCode::Normal)).await.unwrap();
+                }
+            }
+        })
+        .await;
+
+        let transport = Arc::new(
+            WebSocketTransport::builder(&url)
+                .header("Authorization", "Bearer secret")
+                .connect()
+                .await?,
+        );
+        let client = Client::new(transport, None, None);
+        let result = client.request("ping", None).await?;
+        assert_eq!(result["authorization"], "Bearer secret");
+
+        client.request("bye", None).await?;
+        // Once the stream has ended, the client refuses further requests.
+        let ended = tokio::time::timeout(Duration::from_secs(1), async {
+            while client.request("ping", None).await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        ended.await.expect("Stream should end on close");
+        Ok(())
+    }
+
+    /// Tests that an abnormal close is reported before the stream ends.
+    #[tokio::test]
+    async fn test_websocket_abnormal_close() -> Result<(), Error> {
+        let url = serve(|mut socket, _| async move {
+            socket.send(close(CloseCode::Policy)).await.unwrap();
+            while socket.next().await.is_some() {}
+        })
+        .await;
+
+        let transport = WebSocketTransport::connect(&url).await?;
+        let mut messages = transport.receive();
+        let first = tokio::time::timeout(Duration::from_secs(1), messages.next())
+            .await
+            .expect("Expected the close to be reported");
+        assert!(
+            matches!(first, Some(Err(Error::Transport(ref msg))) if msg.contains("bye")),
+            "{:?}",
+            first
+        );
+        assert!(messages.next().await.is_none());
+        Ok(())
+    }
+
+    /// Tests that dropping the transport closes the connection instead of keeping it alive.
+    #[tokio::test]
+    async fn test_websocket_closed_on_drop() -> Result<(), Error> {
+        let (closed_tx, closed_rx) = tokio::sync::oneshot::channel();
+        let url = serve(|mut socket, _| async move {
+            let mut closed = false;
+            while let Some(Ok(frame)) = socket.next().await {
+                closed |= matches!(frame, Frame::Close(_));
+            }
+            let _ = closed_tx.send(closed);
+        })
+        .await;
+
+        let transport = WebSocketTransport::builder(&url)
+            .ping_interval(Some(Duration::from_millis(50)))
+            .connect()
+            .await?;
+        drop(transport);
+        let closed = tokio::time::timeout(Duration::from_secs(1), closed_rx)
+            .await
+            .expect("Connection should close once the transport is dropped");
+        assert_eq!(closed, Ok(true));
+        Ok(())
+    }
+
+    /// Tests that a server that stops responding is detected by the keepalive.
+    #[tokio::test]
+    async fn test_websocket_keepalive_timeout() -> Result<(), Error> {
+        // Never reading means pings are never answered.
+        let url = serve(|socket, _| async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(socket);
+        })
+        .await;
+
+        let transport = WebSocketTransport::builder(&url)
+            .ping_interval(Some(Duration::from_millis(50)))
+            .connect()
+            .await?;
+        let mut messages = transport.receive();
+        let first = tokio::time::timeout(Duration::from_secs(1), messages.next())
+            .await
+            .expect("Expected the keepalive to time out");
+        assert!(
+            matches!(first, Some(Err(Error::Transport(ref msg))) if msg.contains("keepalive")),
+            "{:?}",
+            first
+        );
+        Ok(())
+    }
+}
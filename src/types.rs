@@ -1,17 +1,98 @@
 use serde::{Deserialize, Serialize};
-use std::cmp::PartialEq;
 use std::collections::HashMap;
 
+use crate::error::Error;
+use crate::protocol::RequestId;
+use crate::uri_template::UriTemplate;
+
 /// A progress token, used to associate progress notifications with the original request
-pub type ProgressToken = String;
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProgressToken {
+    String(String),
+    Number(i64),
+}
 
 /// An opaque token used to represent a cursor for pagination
 pub type Cursor = String;
 
+/// Free-form metadata attached to protocol objects under `_meta` (since 2025-06-18)
+pub type Meta = HashMap<String, serde_json::Value>;
+
+/// A revision of the MCP specification
+///
+/// Variants are ordered chronologically, so features can be gated with comparisons
+/// such as `version >= ProtocolVersion::V2025_03_26`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProtocolVersion {
+    V2024_10_07,
+    V2024_11_05,
+    V2025_03_26,
+    V2025_06_18,
+}
+
+impl ProtocolVersion {
+    /// The newest revision this SDK supports.
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V2025_06_18;
+
+    /// The revision's date string, e.g. `"2025-03-26"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtocolVersion::V2024_10_07 => "2024-10-07",
+            ProtocolVersion::V2024_11_05 => "2024-11-05",
+            ProtocolVersion::V2025_03_26 => "2025-03-26",
+            ProtocolVersion::V2025_06_18 => "2025-06-18",
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ProtocolVersion {
+    type Err = Error;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        match version {
+            "2024-10-07" => Ok(ProtocolVersion::V2024_10_07),
+            "2024-11-05" => Ok(ProtocolVersion::V2024_11_05),
+            "2025-03-26" => Ok(ProtocolVersion::V2025_03_26),
+            "2025-06-18" => Ok(ProtocolVersion::V2025_06_18),
+            _ => Err(Error::UnsupportedProtocolVersion {
+                version: version.to_string(),
+                supported: crate::SUPPORTED_PROTOCOL_VERSIONS
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// Adapts a value to what a given protocol revision can express, before it is sent
+///
+/// Fields introduced in later revisions are dropped, and content types the revision
+/// doesn't know are replaced by a textual placeholder, so that one `Client` (or
+/// `Server`) can talk to peers on older revisions.
+pub trait Versioned {
+    fn for_version(self, version: ProtocolVersion) -> Self;
+}
+
+impl<T: Versioned> Versioned for Vec<T> {
+    fn for_version(self, version: ProtocolVersion) -> Self {
+        self.into_iter()
+            .map(|item| item.for_version(version))
+            .collect()
+    }
+}
+
 /// Base request metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestMeta {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "progressToken", skip_serializing_if = "Option::is_none")]
     pub progress_token: Option<ProgressToken>,
 }
 
@@ -23,71 +104,227 @@ pub struct NotificationMeta {
 }
 
 /// Progress information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Progress {
-    /// The progress thus far
-    pub progress: u64,
+    /// The progress thus far; increases with every notification
+    pub progress: f64,
     /// Optional total progress
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub total: Option<u64>,
+    pub total: Option<f64>,
     /// Optional message describing the progress
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 }
 
-/// Resource contents
+/// Parameters of a `notifications/progress` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressNotification {
+    /// The token from the `_meta` of the request this progress belongs to
+    #[serde(rename = "progressToken")]
+    pub progress_token: ProgressToken,
+    #[serde(flatten)]
+    pub progress: Progress,
+}
+
+/// Parameters of a `notifications/cancelled` notification
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelledNotification {
+    /// The ID of the request to cancel
+    #[serde(rename = "requestId")]
+    pub request_id: RequestId,
+    /// Optional reason for the cancellation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Parameters of a `notifications/message` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingMessageNotification {
+    /// The severity of this log message
+    pub level: LoggingLevel,
+    /// Optional name of the logger issuing this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// The data to be logged, such as a string message or an object
+    pub data: serde_json::Value,
+}
+
+/// Parameters of a `notifications/resources/updated` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUpdatedNotification {
+    /// The URI of the resource that has been updated
+    pub uri: String,
+}
+
+/// The contents of a resource, as returned by `resources/read` or embedded in a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ResourceContents {
     Text {
         uri: String,
-        #[serde(rename = "mimeType")]
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
         mime_type: Option<String>,
         text: String,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
     },
     Blob {
         uri: String,
-        #[serde(rename = "mimeType")]
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
         mime_type: Option<String>,
+        /// Base64-encoded binary data
         blob: String,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
     },
 }
-/// A resource in the system
-#[derive(Debug, Clone, Serialize, Deserialize)]
+
+impl Versioned for ResourceContents {
+    fn for_version(self, version: ProtocolVersion) -> Self {
+        if version >= ProtocolVersion::V2025_06_18 {
+            return self;
+        }
+        match self {
+            ResourceContents::Text {
+                uri,
+                mime_type,
+                text,
+                ..
+            } => ResourceContents::Text {
+                uri,
+                mime_type,
+                text,
+                meta: None,
+            },
+            ResourceContents::Blob {
+                uri,
+                mime_type,
+                blob,
+                ..
+            } => ResourceContents::Blob {
+                uri,
+                mime_type,
+                blob,
+                meta: None,
+            },
+        }
+    }
+}
+
+impl ResourceContents {
+    /// The URI of the resource these contents belong to.
+    pub fn uri(&self) -> &str {
+        match self {
+            ResourceContents::Text { uri, .. } | ResourceContents::Blob { uri, .. } => uri,
+        }
+    }
+}
+
+/// A resource the server can read, as listed by `resources/list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Resource {
     /// Unique identifier for the resource
     pub uri: String,
-    /// Human-readable title
-    pub title: String,
+    /// Programmatic name of the resource
+    pub name: String,
+    /// Human-readable title (since 2025-06-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    /// Resource contents
-    pub contents: ResourceContents,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Size of the raw content in bytes, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
 }
 
-/// Model preferences for completion requests
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ModelPreferences {
-    /// Optional model name
+impl Versioned for Resource {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.title = None;
+            self.meta = None;
+        }
+        self.annotations = self.annotations.map(|a| a.for_version(version));
+        self
+    }
+}
+
+/// The sender or recipient of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// Hints on how the client should use or display an object
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotations {
+    /// Who the object is intended for
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub model: Option<String>,
-    /// Optional temperature (0.0 - 1.0)
+    pub audience: Option<Vec<Role>>,
+    /// How important the object is, from 0.0 (optional) to 1.0 (required)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub temperature: Option<f32>,
-    /// Optional maximum number of tokens
+    pub priority: Option<f64>,
+    /// When the object was last modified, as an ISO 8601 timestamp (since 2025-06-18)
+    #[serde(rename = "lastModified", skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Versioned for Annotations {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.last_modified = None;
+        }
+        self
+    }
+}
+
+/// The server's preferences for model selection, requested during sampling
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelPreferences {
+    /// Optional hints to use for model selection, in order of preference
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_tokens: Option<u32>,
+    pub hints: Option<Vec<ModelHint>>,
+    /// How much to prioritize cost when selecting a model (0.0 - 1.0)
+    #[serde(rename = "costPriority", skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f32>,
+    /// How much to prioritize sampling speed (latency) when selecting a model (0.0 - 1.0)
+    #[serde(rename = "speedPriority", skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f32>,
+    /// How much to prioritize intelligence and capabilities when selecting a model (0.0 - 1.0)
+    #[serde(
+        rename = "intelligencePriority",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub intelligence_priority: Option<f32>,
 }
 
-/// A completion result
+/// A hint to use for model selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelHint {
+    /// A hint for a model name, e.g. a substring such as `sonnet`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Completion candidates for an argument value
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Completion {
-    /// The completion text
-    pub text: String,
-    /// Optional score/confidence (0.0 - 1.0)
+    /// Candidate values, at most 100 of them
+    pub values: Vec<String>,
+    /// Total number of candidates, which may exceed the number returned
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub score: Option<f32>,
+    pub total: Option<u64>,
+    /// Whether there are more candidates beyond those returned
+    #[serde(rename = "hasMore", skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
 }
 
 /// Client implementation information
@@ -95,16 +332,48 @@ pub struct Completion {
 pub struct Implementation {
     /// Name of the implementation
     pub name: String,
+    /// Human-readable display name (since 2025-06-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Version of the implementation
     pub version: String,
 }
 
+impl Versioned for Implementation {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.title = None;
+        }
+        self
+    }
+}
+
 /// Client capabilities
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClientCapabilities {
     /// Optional custom capabilities
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, HashMap<String, serde_json::Value>>>,
+    /// Present if the client can answer `roots/list`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
+    /// Present if the client can answer `sampling/createMessage`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingCapability>,
+    /// Present if the client can answer `elicitation/create` (since 2025-06-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<ElicitationCapability>,
+}
+
+impl Versioned for ClientCapabilities {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.elicitation = None;
+        }
+        self
+    }
 }
 
 /// Server capabilities
@@ -120,123 +389,441 @@ pub struct ServerCapabilities {
     pub tools: Option<ToolsCapability>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub logging: Option<LoggingCapability>,
+    /// Present if the server answers `completion/complete` (since 2025-03-26)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
 }
 
-/// Logging levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+impl Versioned for ServerCapabilities {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_03_26 {
+            self.completions = None;
+        }
+        self
+    }
+}
+
+/// Logging levels, as defined by RFC 5424 syslog severities
+///
+/// Ordered by severity, so `LoggingLevel::Debug < LoggingLevel::Emergency`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LoggingLevel {
-    Error,
-    Warn,
-    Info,
     Debug,
-    Trace,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl LoggingLevel {
+    /// The level's name on the wire, e.g. `"warning"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoggingLevel::Debug => "debug",
+            LoggingLevel::Info => "info",
+            LoggingLevel::Notice => "notice",
+            LoggingLevel::Warning => "warning",
+            LoggingLevel::Error => "error",
+            LoggingLevel::Critical => "critical",
+            LoggingLevel::Alert => "alert",
+            LoggingLevel::Emergency => "emergency",
+        }
+    }
+}
+
+impl std::fmt::Display for LoggingLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
-/// A prompt argument
+/// Parameters of a `logging/setLevel` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevelRequest {
+    /// The minimum level of log messages the server should send
+    pub level: LoggingLevel,
+}
+
+/// An argument a prompt template accepts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptArgument {
     pub name: String,
-    pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default_value: Option<String>,
+    pub description: Option<String>,
+    /// Whether this argument must be provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
 }
 
-/// A prompt definition
+/// A prompt or prompt template offered by the server
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Prompt {
-    pub id: String,
     pub name: String,
-    pub description: String,
+    /// Human-readable display name (since 2025-06-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<Vec<PromptArgument>>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
 }
 
-/// Content types for prompt messages
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Versioned for Prompt {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.title = None;
+            self.meta = None;
+        }
+        self
+    }
+}
+
+impl Prompt {
+    /// Names of the arguments marked as required.
+    pub fn required_arguments(&self) -> impl Iterator<Item = &str> {
+        self.arguments
+            .iter()
+            .flatten()
+            .filter(|arg| arg.required.unwrap_or(false))
+            .map(|arg| arg.name.as_str())
+    }
+}
+
+/// Content of a prompt message, sampling message or tool result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum MessageContent {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
+    },
+    /// A base64-encoded image
     #[serde(rename = "image")]
     Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
+    },
+    /// Base64-encoded audio (since 2025-03-26)
+    #[serde(rename = "audio")]
+    Audio {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
+    },
+    /// A link to a resource the client may read (since 2025-06-18)
+    #[serde(rename = "resource_link")]
+    ResourceLink {
         uri: String,
-        alt_text: Option<String>,
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
     },
+    /// The contents of a resource, embedded in the message
     #[serde(rename = "resource")]
-    Resource { resource: Resource },
+    Resource {
+        resource: ResourceContents,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+        #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+        meta: Option<Meta>,
+    },
 }
 
-impl MessageContent {
-    pub fn contains(&self, substring: &str) -> bool {
-        match self {
-            MessageContent::Text { text } => text.contains(substring),
-            MessageContent::Image { alt_text, .. } => alt_text
-                .as_ref()
-                .map(|text| text.contains(substring))
-                .unwrap_or(false),
-            MessageContent::Resource { resource } => {
-                resource.title.contains(substring)
-                    || resource
-                        .description
-                        .as_ref()
-                        .map(|d| d.contains(substring))
-                        .unwrap_or(false)
+impl Versioned for MessageContent {
+    fn for_version(self, version: ProtocolVersion) -> Self {
+        let mut content = match self {
+            MessageContent::Audio { mime_type, .. } if version < ProtocolVersion::V2025_03_26 => {
+                return MessageContent::text(format!("[{} audio omitted]", mime_type));
             }
+            MessageContent::ResourceLink { uri, name, .. }
+                if version < ProtocolVersion::V2025_06_18 =>
+            {
+                return MessageContent::text(format!("[{}]({})", name, uri));
+            }
+            MessageContent::Resource {
+                resource,
+                annotations,
+                meta,
+            } => MessageContent::Resource {
+                resource: resource.for_version(version),
+                annotations,
+                meta,
+            },
+            content => content,
+        };
+        let (MessageContent::Text {
+            annotations, meta, ..
+        }
+        | MessageContent::Image {
+            annotations, meta, ..
+        }
+        | MessageContent::Audio {
+            annotations, meta, ..
+        }
+        | MessageContent::ResourceLink {
+            annotations, meta, ..
+        }
+        | MessageContent::Resource {
+            annotations, meta, ..
+        }) = &mut content;
+        *annotations = annotations.take().map(|a| a.for_version(version));
+        if version < ProtocolVersion::V2025_06_18 {
+            *meta = None;
         }
+        content
     }
 }
 
-impl PartialEq for MessageContent {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (MessageContent::Text { text: t1 }, MessageContent::Text { text: t2 }) => t1 == t2,
-            (
-                MessageContent::Image {
-                    uri: u1,
-                    alt_text: a1,
-                },
-                MessageContent::Image {
-                    uri: u2,
-                    alt_text: a2,
-                },
-            ) => u1 == u2 && a1 == a2,
-            (
-                MessageContent::Resource { resource: r1 },
-                MessageContent::Resource { resource: r2 },
-            ) => r1.uri == r2.uri,
-            _ => false,
+impl MessageContent {
+    /// Plain text content without annotations.
+    pub fn text(text: impl Into<String>) -> Self {
+        MessageContent::Text {
+            text: text.into(),
+            annotations: None,
+            meta: None,
+        }
+    }
+
+    /// Whether any text in this content contains `substring`.
+    pub fn contains(&self, substring: &str) -> bool {
+        match self {
+            MessageContent::Text { text, .. } => text.contains(substring),
+            MessageContent::Image { .. } | MessageContent::Audio { .. } => false,
+            MessageContent::ResourceLink {
+                name,
+                title,
+                description,
+                ..
+            } => {
+                name.contains(substring)
+                    || [title, description]
+                        .into_iter()
+                        .flatten()
+                        .any(|text| text.contains(substring))
+            }
+            MessageContent::Resource { resource, .. } => match resource {
+                ResourceContents::Text { text, .. } => text.contains(substring),
+                ResourceContents::Blob { .. } => false,
+            },
         }
     }
 }
 
-/// A prompt message
+/// A message returned as part of a prompt
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptMessage {
     pub role: String,
-    pub content: Vec<MessageContent>,
+    pub content: MessageContent,
 }
 
+impl Versioned for PromptMessage {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.content = self.content.for_version(version);
+        self
+    }
+}
+
+/// List tools request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListToolsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Cursor>,
+}
+
+/// List tools result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListToolsResult {
     pub tools: Vec<Tool>,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Cursor>,
+}
+
+impl Versioned for ListToolsResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.tools = self.tools.for_version(version);
+        self
+    }
 }
 
 /// A tool definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
     pub name: String,
+    /// Human-readable display name (since 2025-06-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Description of the tool; empty if the server didn't provide one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: serde_json::Value,
+    /// JSON Schema of the tool's `structuredContent` (since 2025-06-18)
+    #[serde(rename = "outputSchema", skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+    /// Hints about the tool's behavior (since 2025-03-26)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+impl Versioned for Tool {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_03_26 {
+            self.annotations = None;
+        }
+        if version < ProtocolVersion::V2025_06_18 {
+            self.title = None;
+            self.output_schema = None;
+            self.meta = None;
+        }
+        self
+    }
 }
 
-/// Root definition
+/// Hints describing a tool's behavior
+///
+/// These are hints only; clients must not rely on them for tools from untrusted servers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolAnnotations {
+    /// Human-readable title for the tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// If true, the tool does not modify its environment (default: false)
+    #[serde(rename = "readOnlyHint", skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// If true, the tool may perform destructive updates (default: true)
+    #[serde(rename = "destructiveHint", skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// If true, repeated calls with the same arguments have no additional effect (default: false)
+    #[serde(rename = "idempotentHint", skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// If true, the tool interacts with an open world of external entities (default: true)
+    #[serde(rename = "openWorldHint", skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+/// A root directory or file the server may operate on
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Root {
-    pub id: String,
-    pub name: String,
-    pub description: String,
+    /// The URI identifying the root; must start with `file://` for now
+    pub uri: String,
+    /// Optional human-readable name for the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Result of a `roots/list` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
+
+/// A message in a sampling request or result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    pub role: String,
+    pub content: MessageContent,
+}
+
+/// Parameters of a `sampling/createMessage` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMessageRequest {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(rename = "modelPreferences", skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    #[serde(rename = "systemPrompt", skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Which MCP servers' context to include: `none`, `thisServer` or `allServers`
+    #[serde(rename = "includeContext", skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(rename = "maxTokens")]
+    pub max_tokens: u32,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Result of a `sampling/createMessage` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMessageResult {
+    pub role: String,
+    pub content: MessageContent,
+    /// The name of the model that generated the message
+    pub model: String,
+    /// Why sampling stopped, e.g. `endTurn`, `stopSequence` or `maxTokens`
+    #[serde(rename = "stopReason", skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+impl Versioned for CreateMessageResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.content = self.content.for_version(version);
+        self
+    }
+}
+
+/// Parameters of an `elicitation/create` request (since 2025-06-18)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElicitRequest {
+    /// The message to present to the user
+    pub message: String,
+    /// A flat JSON Schema object describing the requested fields, using primitive types only
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: serde_json::Value,
+}
+
+/// How the user responded to an elicitation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// The user submitted the form
+    Accept,
+    /// The user explicitly declined
+    Decline,
+    /// The user dismissed the request without choosing
+    Cancel,
+}
+
+/// Result of an `elicitation/create` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElicitResult {
+    pub action: ElicitAction,
+    /// The submitted values, present when `action` is `accept`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// Initialize request
@@ -259,6 +846,14 @@ pub struct InitializeResult {
     pub capabilities: ServerCapabilities,
 }
 
+impl Versioned for InitializeResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.server_info = self.server_info.for_version(version);
+        self.capabilities = self.capabilities.for_version(version);
+        self
+    }
+}
+
 /// List resources request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListResourcesRequest {
@@ -272,10 +867,78 @@ pub struct ListResourcesRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListResourcesResult {
     pub resources: Vec<Resource>,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Cursor>,
+}
+
+impl Versioned for ListResourcesResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.resources = self.resources.for_version(version);
+        self
+    }
+}
+
+/// A template describing a family of resources by an RFC 6570 URI template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    /// URI template, e.g. `file:///{+path}`
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    /// Human-readable name
+    pub name: String,
+    /// Optional description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type shared by all matching resources, if any
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Human-readable display name (since 2025-06-18)
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+impl Versioned for ResourceTemplate {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        if version < ProtocolVersion::V2025_06_18 {
+            self.title = None;
+            self.meta = None;
+        }
+        self
+    }
+}
+
+impl ResourceTemplate {
+    /// Parses the template's URI template.
+    pub fn parse_template(&self) -> Result<UriTemplate, Error> {
+        UriTemplate::parse(&self.uri_template)
+    }
+}
+
+/// List resource templates request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Cursor>,
+}
+
+/// List resource templates result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourceTemplatesResult {
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<ResourceTemplate>,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<Cursor>,
 }
 
+impl Versioned for ListResourceTemplatesResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.resource_templates = self.resource_templates.for_version(version);
+        self
+    }
+}
+
 /// List prompts request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListPromptsRequest {
@@ -287,18 +950,62 @@ pub struct ListPromptsRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListPromptsResult {
     pub prompts: Vec<Prompt>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<Cursor>,
 }
 
-/// Complete request
+impl Versioned for ListPromptsResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.prompts = self.prompts.for_version(version);
+        self
+    }
+}
+
+/// Get prompt request
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CompleteRequest {
-    pub prompt_id: String,
+pub struct GetPromptRequest {
+    pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub model_preferences: Option<ModelPreferences>,
+}
+
+/// What a completion request is completing an argument for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// An argument of a prompt, identified by name
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    /// A variable of a resource template, identified by its URI template
+    #[serde(rename = "ref/resource")]
+    Resource { uri: String },
+}
+
+impl CompletionReference {
+    /// References the prompt called `name`.
+    pub fn prompt(name: impl Into<String>) -> Self {
+        CompletionReference::Prompt { name: name.into() }
+    }
+
+    /// References the resource template `uri`.
+    pub fn resource(uri: impl Into<String>) -> Self {
+        CompletionReference::Resource { uri: uri.into() }
+    }
+}
+
+/// The argument being completed and its partial value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    pub name: String,
+    pub value: String,
+}
+
+/// Complete request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteRequest {
+    #[serde(rename = "ref")]
+    pub reference: CompletionReference,
+    pub argument: CompletionArgument,
 }
 
 /// Complete result
@@ -321,6 +1028,21 @@ pub struct ResourcesCapability {
     pub list_changed: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RootsCapability {
+    #[serde(rename = "listChanged", skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SamplingCapability {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElicitationCapability {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompletionsCapability {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolsCapability {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -335,11 +1057,40 @@ pub struct GetPromptResult {
     pub messages: Vec<PromptMessage>,
 }
 
+impl Versioned for GetPromptResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.messages = self.messages.for_version(version);
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallToolResult {
     pub content: Vec<MessageContent>,
+    /// Structured result matching the tool's `outputSchema` (since 2025-06-18)
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
     #[serde(rename = "isError", default)]
     pub is_error: bool,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+impl Versioned for CallToolResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.content = self.content.for_version(version);
+        if version < ProtocolVersion::V2025_06_18 {
+            // Older clients only see `content`, so make sure the structured result is in it.
+            if let Some(structured) = self.structured_content.take() {
+                if self.content.is_empty() {
+                    self.content
+                        .push(MessageContent::text(structured.to_string()));
+                }
+            }
+            self.meta = None;
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -353,8 +1104,186 @@ pub struct ReadResourceResult {
     pub contents: Vec<ResourceContents>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LoggingCapability {
-    // If the server supports logging, define fields as needed
-    // If not returned by server, you can leave this empty
+impl Versioned for ReadResourceResult {
+    fn for_version(mut self, version: ProtocolVersion) -> Self {
+        self.contents = self.contents.for_version(version);
+        self
+    }
+}
+
+/// Present if the server sends log messages and accepts `logging/setLevel`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingCapability {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_versions_parse() {
+        for version in crate::SUPPORTED_PROTOCOL_VERSIONS {
+            let parsed: ProtocolVersion = version.parse().unwrap();
+            assert_eq!(parsed.as_str(), *version);
+        }
+        assert_eq!(
+            crate::LATEST_PROTOCOL_VERSION
+                .parse::<ProtocolVersion>()
+                .unwrap(),
+            ProtocolVersion::LATEST
+        );
+        assert!("2023-01-01".parse::<ProtocolVersion>().is_err());
+        assert!(ProtocolVersion::V2024_11_05 < ProtocolVersion::V2025_03_26);
+    }
+
+    #[test]
+    fn test_tool_for_version() {
+        let tool: Tool = serde_json::from_value(serde_json::json!({
+            "name": "get_weather",
+            "title": "Weather",
+            "inputSchema": { "type": "object" },
+            "outputSchema": { "type": "object" },
+            "annotations": { "readOnlyHint": true, "openWorldHint": true },
+            "_meta": { "example.com/owner": "weather-team" }
+        }))
+        .unwrap();
+        assert_eq!(tool.description, "");
+        assert_eq!(
+            tool.annotations.as_ref().unwrap().read_only_hint,
+            Some(true)
+        );
+
+        let latest =
+            serde_json::to_value(tool.clone().for_version(ProtocolVersion::LATEST)).unwrap();
+        assert_eq!(latest["_meta"]["example.com/owner"], "weather-team");
+        assert!(latest.get("description").is_none());
+
+        let march =
+            serde_json::to_value(tool.clone().for_version(ProtocolVersion::V2025_03_26)).unwrap();
+        assert!(march.get("annotations").is_some());
+        assert!(march.get("title").is_none());
+        assert!(march.get("outputSchema").is_none());
+
+        let november =
+            serde_json::to_value(tool.for_version(ProtocolVersion::V2024_11_05)).unwrap();
+        assert!(november.get("annotations").is_none());
+    }
+
+    #[test]
+    fn test_call_tool_result_for_version() {
+        let result: CallToolResult = serde_json::from_value(serde_json::json!({
+            "content": [
+                { "type": "resource_link", "uri": "file:///report.pdf", "name": "report.pdf" }
+            ],
+            "structuredContent": { "temperature": 21.5 }
+        }))
+        .unwrap();
+        assert!(!result.is_error);
+        assert_eq!(result.content.len(), 1);
+        assert!(matches!(
+            result.content[0],
+            MessageContent::ResourceLink { ref name, .. } if name == "report.pdf"
+        ));
+
+        let old = result.for_version(ProtocolVersion::V2025_03_26);
+        assert!(old.structured_content.is_none());
+        assert_eq!(
+            old.content,
+            vec![MessageContent::text("[report.pdf](file:///report.pdf)")]
+        );
+
+        let structured_only = CallToolResult {
+            content: vec![],
+            structured_content: Some(serde_json::json!({ "temperature": 21.5 })),
+            is_error: false,
+            meta: None,
+        };
+        let old = structured_only.for_version(ProtocolVersion::V2024_11_05);
+        assert_eq!(
+            old.content,
+            vec![MessageContent::text(r#"{"temperature":21.5}"#)]
+        );
+    }
+
+    #[test]
+    fn test_content_round_trip() {
+        let spec = serde_json::json!({
+            "content": [
+                {
+                    "type": "text",
+                    "text": "Current weather in New York: 72°F",
+                    "annotations": { "audience": ["user", "assistant"], "priority": 0.9 }
+                },
+                { "type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png" },
+                { "type": "audio", "data": "UklGRg==", "mimeType": "audio/wav" },
+                {
+                    "type": "resource_link",
+                    "uri": "file:///project/src/main.rs",
+                    "name": "main.rs",
+                    "description": "Primary application entry point",
+                    "mimeType": "text/x-rust",
+                    "annotations": { "lastModified": "2025-01-12T15:00:58Z" }
+                },
+                {
+                    "type": "resource",
+                    "resource": {
+                        "uri": "resource://example",
+                        "mimeType": "text/plain",
+                        "text": "Resource content"
+                    },
+                    "annotations": { "audience": ["user"] }
+                },
+                {
+                    "type": "resource",
+                    "resource": { "uri": "file:///logo.png", "blob": "iVBORw0KGgo=" }
+                }
+            ],
+            "isError": false
+        });
+        let result: CallToolResult = serde_json::from_value(spec.clone()).unwrap();
+        match &result.content[0] {
+            MessageContent::Text {
+                annotations: Some(annotations),
+                ..
+            } => {
+                assert_eq!(
+                    annotations.audience,
+                    Some(vec![Role::User, Role::Assistant])
+                );
+                assert_eq!(annotations.priority, Some(0.9));
+            }
+            other => panic!("Expected annotated text, got {:?}", other),
+        }
+        assert!(matches!(
+            &result.content[1],
+            MessageContent::Image { mime_type, .. } if mime_type == "image/png"
+        ));
+        assert!(matches!(
+            &result.content[5],
+            MessageContent::Resource { resource: ResourceContents::Blob { uri, .. }, .. }
+                if uri == "file:///logo.png"
+        ));
+        assert!(result.content[4].contains("Resource content"));
+        assert_eq!(serde_json::to_value(&result).unwrap(), spec);
+    }
+
+    #[test]
+    fn test_resource_round_trip() {
+        let spec = serde_json::json!({
+            "resources": [
+                {
+                    "uri": "file:///project/src/main.rs",
+                    "name": "main.rs",
+                    "title": "Rust Software Application Main File",
+                    "description": "Primary application entry point",
+                    "mimeType": "text/x-rust"
+                },
+                { "uri": "file:///project/logo.png", "name": "logo.png", "size": 2048 }
+            ],
+            "nextCursor": "next-page-cursor"
+        });
+        let result: ListResourcesResult = serde_json::from_value(spec.clone()).unwrap();
+        assert_eq!(result.resources[0].name, "main.rs");
+        assert_eq!(result.resources[1].size, Some(2048));
+        assert_eq!(serde_json::to_value(&result).unwrap(), spec);
+    }
 }
@@ -0,0 +1,691 @@
+//! RFC 6570 URI templates, as used by MCP resource templates.
+//!
+//! [`UriTemplate::expand`](crate::uri_template::UriTemplate::expand) fills a template such
+//! as `file:///{+path}` from variable values (levels 1-4, except associative-array values).
+//! [`UriTemplate::match_uri`](crate::uri_template::UriTemplate::match_uri) does the reverse,
+//! extracting variable values from a concrete URI, which servers use to route
+//! `resources/read` requests to the template that produced the URI.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::error::{Error, ErrorCode};
+
+/// A value substituted for a template variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateValue {
+    String(String),
+    List(Vec<String>),
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        TemplateValue::String(value.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        TemplateValue::String(value)
+    }
+}
+
+impl From<Vec<String>> for TemplateValue {
+    fn from(value: Vec<String>) -> Self {
+        TemplateValue::List(value)
+    }
+}
+
+impl From<Vec<&str>> for TemplateValue {
+    fn from(value: Vec<&str>) -> Self {
+        TemplateValue::List(value.into_iter().map(String::from).collect())
+    }
+}
+
+/// Expression operator, which decides prefix, separator and encoding of its expansion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    Path,
+    PathParam,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Operator::Reserved),
+            '#' => Some(Operator::Fragment),
+            '.' => Some(Operator::Label),
+            '/' => Some(Operator::Path),
+            ';' => Some(Operator::PathParam),
+            '?' => Some(Operator::Query),
+            '&' => Some(Operator::QueryContinuation),
+            _ => None,
+        }
+    }
+
+    fn first(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved => "",
+            Operator::Fragment => "#",
+            Operator::Label => ".",
+            Operator::Path => "/",
+            Operator::PathParam => ";",
+            Operator::Query => "?",
+            Operator::QueryContinuation => "&",
+        }
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved | Operator::Fragment => ",",
+            Operator::Label => ".",
+            Operator::Path => "/",
+            Operator::PathParam => ";",
+            Operator::Query | Operator::QueryContinuation => "&",
+        }
+    }
+
+    fn named(self) -> bool {
+        matches!(
+            self,
+            Operator::PathParam | Operator::Query | Operator::QueryContinuation
+        )
+    }
+
+    fn if_empty(self) -> &'static str {
+        match self {
+            Operator::Query | Operator::QueryContinuation => "=",
+            _ => "",
+        }
+    }
+
+    fn allows_reserved(self) -> bool {
+        matches!(self, Operator::Reserved | Operator::Fragment)
+    }
+
+    /// Whether a matched expansion may contain `c`.
+    fn may_contain(self, c: char) -> bool {
+        match self {
+            Operator::Reserved => true,
+            Operator::Fragment => true,
+            Operator::Path => c != '?' && c != '#',
+            Operator::Query | Operator::QueryContinuation => c != '#',
+            Operator::Simple | Operator::Label | Operator::PathParam => {
+                c != '/' && c != '?' && c != '#'
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VarSpec {
+    name: String,
+    prefix: Option<usize>,
+    explode: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Expression(Operator, Vec<VarSpec>),
+}
+
+/// A parsed RFC 6570 URI template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTemplate {
+    template: String,
+    parts: Vec<Part>,
+}
+
+impl UriTemplate {
+    /// Parses `template`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ErrorCode::InvalidParams` for unbalanced braces, empty expressions
+    /// or malformed variable modifiers.
+    pub fn parse(template: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| {
+            Error::protocol(
+                ErrorCode::InvalidParams,
+                format!("Invalid URI template '{}': {}", template, reason),
+            )
+        };
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            match rest.find('{') {
+                Some(0) => {
+                    let end = rest.find('}').ok_or_else(|| invalid("unclosed '{'"))?;
+                    parts.push(Self::parse_expression(&rest[1..end]).map_err(|r| invalid(&r))?);
+                    rest = &rest[end + 1..];
+                }
+                Some(start) => {
+                    parts.push(Part::Literal(rest[..start].to_string()));
+                    rest = &rest[start..];
+                }
+                None => {
+                    parts.push(Part::Literal(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+        if parts
+            .iter()
+            .any(|part| matches!(part, Part::Literal(text) if text.contains('}')))
+        {
+            return Err(invalid("unexpected '}'"));
+        }
+
+        Ok(Self {
+            template: template.to_string(),
+            parts,
+        })
+    }
+
+    fn parse_expression(expression: &str) -> Result<Part, String> {
+        let mut chars = expression.chars();
+        let (operator, vars) = match chars.next().and_then(Operator::from_char) {
+            Some(operator) => (operator, chars.as_str()),
+            None => (Operator::Simple, expression),
+        };
+        if vars.is_empty() {
+            return Err("empty expression".to_string());
+        }
+
+        let specs = vars
+            .split(',')
+            .map(|spec| {
+                let (name, prefix, explode) = if let Some(name) = spec.strip_suffix('*') {
+                    (name, None, true)
+                } else if let Some((name, length)) = spec.split_once(':') {
+                    let length = length
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid prefix length in '{}'", spec))?;
+                    (name, Some(length), false)
+                } else {
+                    (spec, None, false)
+                };
+                let valid = !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '%');
+                if !valid {
+                    return Err(format!("invalid variable name '{}'", name));
+                }
+                Ok(VarSpec {
+                    name: name.to_string(),
+                    prefix,
+                    explode,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Part::Expression(operator, specs))
+    }
+
+    /// The template string this was parsed from.
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Names of all variables in the template, in order of appearance.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().flat_map(|part| match part {
+            Part::Expression(_, specs) => specs.iter().map(|s| s.name.as_str()).collect(),
+            Part::Literal(_) => Vec::new(),
+        })
+    }
+
+    /// Expands the template, leaving out variables missing from `variables`.
+    pub fn expand(&self, variables: &HashMap<String, TemplateValue>) -> String {
+        let mut uri = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => uri.push_str(text),
+                Part::Expression(operator, specs) => {
+                    let expanded: Vec<String> = specs
+                        .iter()
+                        .filter_map(|spec| {
+                            variables
+                                .get(&spec.name)
+                                .and_then(|value| Self::expand_var(*operator, spec, value))
+                        })
+                        .collect();
+                    if !expanded.is_empty() {
+                        uri.push_str(operator.first());
+                        uri.push_str(&expanded.join(operator.separator()));
+                    }
+                }
+            }
+        }
+        uri
+    }
+
+    fn expand_var(operator: Operator, spec: &VarSpec, value: &TemplateValue) -> Option<String> {
+        let reserved = operator.allows_reserved();
+        match value {
+            TemplateValue::String(value) => {
+                let value = match spec.prefix {
+                    Some(length) => value.chars().take(length).collect(),
+                    None => value.clone(),
+                };
+                let value = encode(&value, reserved);
+                if !operator.named() {
+                    Some(value)
+                } else if value.is_empty() {
+                    Some(format!("{}{}", spec.name, operator.if_empty()))
+                } else {
+                    Some(format!("{}={}", spec.name, value))
+                }
+            }
+            TemplateValue::List(items) if items.is_empty() => None,
+            TemplateValue::List(items) => {
+                let items = items.iter().map(|item| encode(item, reserved));
+                if !spec.explode {
+                    let joined = items.collect::<Vec<_>>().join(",");
+                    if operator.named() {
+                        Some(format!("{}={}", spec.name, joined))
+                    } else {
+                        Some(joined)
+                    }
+                } else if operator.named() {
+                    let items = items.map(|item| {
+                        if item.is_empty() {
+                            format!("{}{}", spec.name, operator.if_empty())
+                        } else {
+                            format!("{}={}", spec.name, item)
+                        }
+                    });
+                    Some(items.collect::<Vec<_>>().join(operator.separator()))
+                } else {
+                    Some(items.collect::<Vec<_>>().join(operator.separator()))
+                }
+            }
+        }
+    }
+
+    /// Matches `uri` against the template, returning the (percent-decoded) value of each
+    /// variable present in it, or `None` if the URI can't have come from this template.
+    ///
+    /// Exploded and list values are returned joined by their separator, e.g. `{/path*}`
+    /// matching `/a/b` yields `path = "a/b"`.
+    ///
+    /// An expression followed by a literal may end at any occurrence of that literal, so
+    /// `{+a}-{b}-c` matches `1-2-c`; the latest ending that lets the rest of the URI match
+    /// wins. Adjacent expressions aren't backtracked into: the first one takes every
+    /// character it may contain, except that a `{?…}` or `{&…}` expression stops before a
+    /// following `{&…}` expression's first `&name=`.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut variables = HashMap::new();
+        let mut failed = HashSet::new();
+        self.match_from(0, uri, 0, &mut variables, &mut failed)
+            .then_some(variables)
+    }
+
+    /// Matches the parts from `index` on against `uri[offset..]`. Remembering which
+    /// `(index, offset)` states already failed keeps the work polynomial in the length of
+    /// the URI, which is up to the client.
+    fn match_from(
+        &self,
+        index: usize,
+        uri: &str,
+        offset: usize,
+        variables: &mut HashMap<String, String>,
+        failed: &mut HashSet<(usize, usize)>,
+    ) -> bool {
+        let rest = &uri[offset..];
+        let Some(part) = self.parts.get(index) else {
+            return rest.is_empty();
+        };
+        if failed.contains(&(index, offset)) {
+            return false;
+        }
+        let matched = match part {
+            Part::Literal(text) => {
+                rest.starts_with(text.as_str())
+                    && self.match_from(index + 1, uri, offset + text.len(), variables, failed)
+            }
+            Part::Expression(operator, specs) => {
+                Self::expression_ends(*operator, rest, self.parts.get(index + 1))
+                    .into_iter()
+                    .any(|end| {
+                        let mut candidate = variables.clone();
+                        let matched =
+                            Self::match_expression(*operator, specs, &rest[..end], &mut candidate)
+                                && self.match_from(
+                                    index + 1,
+                                    uri,
+                                    offset + end,
+                                    &mut candidate,
+                                    failed,
+                                );
+                        if matched {
+                            *variables = candidate;
+                        }
+                        matched
+                    })
+            }
+        };
+        if !matched {
+            failed.insert((index, offset));
+        }
+        matched
+    }
+
+    /// Where the expansion of an expression at the start of `uri` may end, latest first:
+    /// no further than the characters it may contain reach, and at a place where the
+    /// following literal or `{&…}` expression can start.
+    fn expression_ends(operator: Operator, uri: &str, next: Option<&Part>) -> Vec<usize> {
+        let span = uri
+            .char_indices()
+            .find(|(_, c)| !operator.may_contain(*c))
+            .map_or(uri.len(), |(i, _)| i);
+        match next {
+            Some(Part::Literal(literal)) => {
+                let mut ends: Vec<usize> = uri
+                    .match_indices(literal.as_str())
+                    .map(|(start, _)| start)
+                    .take_while(|start| *start <= span)
+                    .collect();
+                ends.reverse();
+                ends
+            }
+            Some(Part::Expression(Operator::QueryContinuation, specs))
+                if matches!(operator, Operator::Query | Operator::QueryContinuation) =>
+            {
+                let boundary = uri[..span].match_indices('&').map(|(i, _)| i).find(|i| {
+                    let pair = &uri[i + 1..span];
+                    let name = pair.split(['=', '&']).next().unwrap_or(pair);
+                    specs.iter().any(|spec| spec.name == name)
+                });
+                vec![boundary.unwrap_or(span)]
+            }
+            _ => vec![span],
+        }
+    }
+
+    fn match_expression(
+        operator: Operator,
+        specs: &[VarSpec],
+        text: &str,
+        variables: &mut HashMap<String, String>,
+    ) -> bool {
+        if text.is_empty() {
+            // Prefixed expressions of undefined variables expand to nothing. Unprefixed
+            // ones must match something, so that every variable they name is set.
+            return !operator.first().is_empty();
+        }
+        let Some(body) = text.strip_prefix(operator.first()) else {
+            return false;
+        };
+
+        if operator.named() {
+            for pair in body.split(operator.separator()) {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                if !specs.iter().any(|spec| spec.name == name) {
+                    return false;
+                }
+                let value = decode(value);
+                variables
+                    .entry(name.to_string())
+                    .and_modify(|existing| {
+                        existing.push(',');
+                        existing.push_str(&value);
+                    })
+                    .or_insert(value);
+            }
+            return true;
+        }
+
+        let separator = operator.separator();
+        if specs.len() == 1 {
+            let spec = &specs[0];
+            let value = if spec.explode {
+                body.split(separator)
+                    .map(decode)
+                    .collect::<Vec<_>>()
+                    .join(separator)
+            } else {
+                decode(body)
+            };
+            if !spec.explode && body.contains(separator) && separator != "," {
+                return false;
+            }
+            variables.insert(spec.name.clone(), value);
+            return true;
+        }
+
+        let values: Vec<&str> = body.split(separator).collect();
+        if values.len() > specs.len() {
+            return false;
+        }
+        for (spec, value) in specs.iter().zip(values) {
+            variables.insert(spec.name.clone(), decode(value));
+        }
+        true
+    }
+}
+
+impl fmt::Display for UriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.template)
+    }
+}
+
+impl std::str::FromStr for UriTemplate {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Self::parse(template)
+    }
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn is_reserved(c: char) -> bool {
+    matches!(
+        c,
+        ':' | '/'
+            | '?'
+            | '#'
+            | '['
+            | ']'
+            | '@'
+            | '!'
+            | '$'
+            | '&'
+            | '\''
+            | '('
+            | ')'
+            | '*'
+            | '+'
+            | ','
+            | ';'
+            | '='
+    )
+}
+
+/// Percent-encodes `value`, keeping reserved characters and existing escapes if `allow_reserved`.
+fn encode(value: &str, allow_reserved: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    let bytes = value.as_bytes();
+    for (i, c) in value.char_indices() {
+        let is_escape = c == '%'
+            && bytes.len() > i + 2
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit();
+        if is_unreserved(c) || (allow_reserved && (is_reserved(c) || is_escape)) {
+            encoded.push(c);
+        } else {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes percent-escapes in `value`, leaving malformed ones untouched.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = |b: u8| (b as char).to_digit(16).unwrap_or(0) as u8;
+            decoded.push(hex(bytes[i + 1]) << 4 | hex(bytes[i + 2]));
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc_variables() -> HashMap<String, TemplateValue> {
+        HashMap::from([
+            ("var".to_string(), "value".into()),
+            ("hello".to_string(), "Hello World!".into()),
+            ("path".to_string(), "/foo/bar".into()),
+            ("empty".to_string(), "".into()),
+            ("x".to_string(), "1024".into()),
+            ("y".to_string(), "768".into()),
+            ("list".to_string(), vec!["red", "green", "blue"].into()),
+        ])
+    }
+
+    fn expand(template: &str) -> String {
+        UriTemplate::parse(template)
+            .unwrap()
+            .expand(&rfc_variables())
+    }
+
+    #[test]
+    fn test_expand_rfc_examples() {
+        assert_eq!(expand("{var}"), "value");
+        assert_eq!(expand("{hello}"), "Hello%20World%21");
+        assert_eq!(expand("{+hello}"), "Hello%20World!");
+        assert_eq!(expand("{+path}/here"), "/foo/bar/here");
+        assert_eq!(expand("{#path}"), "#/foo/bar");
+        assert_eq!(expand("X{.var}"), "X.value");
+        assert_eq!(expand("{/var,x}/here"), "/value/1024/here");
+        assert_eq!(expand("{;x,y,empty}"), ";x=1024;y=768;empty");
+        assert_eq!(expand("{?x,y,empty}"), "?x=1024&y=768&empty=");
+        assert_eq!(expand("?fixed=yes{&x}"), "?fixed=yes&x=1024");
+        assert_eq!(expand("{var:3}"), "val");
+        assert_eq!(expand("{list}"), "red,green,blue");
+        assert_eq!(expand("{/list*}"), "/red/green/blue");
+        assert_eq!(expand("{?list*}"), "?list=red&list=green&list=blue");
+        assert_eq!(expand("{undefined}"), "");
+        assert_eq!(expand("{?undefined,x}"), "?x=1024");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(UriTemplate::parse("file:///{path").is_err());
+        assert!(UriTemplate::parse("file:///{}").is_err());
+        assert!(UriTemplate::parse("file:///path}").is_err());
+        assert!(UriTemplate::parse("{var:abc}").is_err());
+    }
+
+    #[test]
+    fn test_match_uri() {
+        let template = UriTemplate::parse("file:///{+path}").unwrap();
+        let vars = template.match_uri("file:///src/main.rs").unwrap();
+        assert_eq!(vars["path"], "src/main.rs");
+
+        let template = UriTemplate::parse("db://{table}/{id}{?fields}").unwrap();
+        let vars = template
+            .match_uri("db://users/42?fields=name%20first")
+            .unwrap();
+        assert_eq!(vars["table"], "users");
+        assert_eq!(vars["id"], "42");
+        assert_eq!(vars["fields"], "name first");
+        assert!(template.match_uri("db://users/42/extra").is_none());
+        assert!(template.match_uri("other://users/42").is_none());
+
+        let template = UriTemplate::parse("users://{id}/profile").unwrap();
+        assert!(template.match_uri("users:///profile").is_none());
+        let template = UriTemplate::parse("file:///{+path}").unwrap();
+        assert!(template.match_uri("file:///").is_none());
+    }
+
+    #[test]
+    fn test_match_adjacent_expressions_without_backtracking() {
+        // Exponential with backtracking; must return promptly.
+        let template = UriTemplate::parse(&"{a}{b}{c}{d}{e}{f}{g}{h}".repeat(4)).unwrap();
+        let uri = format!("{}/", "x".repeat(1000));
+        assert!(template.match_uri(&uri).is_none());
+
+        let template = UriTemplate::parse("{+path}/here").unwrap();
+        assert_eq!(
+            template.match_uri("/foo/bar/here").unwrap()["path"],
+            "/foo/bar"
+        );
+    }
+
+    #[test]
+    fn test_match_query_followed_by_continuation() {
+        let template = UriTemplate::parse("search{?x}{&y}").unwrap();
+        let vars = template.match_uri("search?x=1&y=2").unwrap();
+        assert_eq!(vars["x"], "1");
+        assert_eq!(vars["y"], "2");
+
+        let vars = template.match_uri("search?x=1%262").unwrap();
+        assert_eq!(vars["x"], "1&2");
+        assert!(!vars.contains_key("y"));
+    }
+
+    #[test]
+    fn test_match_backtracks_over_literal_separators() {
+        let template = UriTemplate::parse("{+a}-{b}-c").unwrap();
+        let vars = template.match_uri("1-2-c").unwrap();
+        assert_eq!(vars["a"], "1");
+        assert_eq!(vars["b"], "2");
+
+        let vars = template.match_uri("1-2-3-c").unwrap();
+        assert_eq!(vars["a"], "1-2");
+        assert_eq!(vars["b"], "3");
+        assert!(template.match_uri("1-2-d").is_none());
+
+        // Many literal occurrences must not blow up the search.
+        let template = UriTemplate::parse(&"{+a}-".repeat(8)).unwrap();
+        assert!(template.match_uri(&format!("{}x", "-".repeat(200))).is_none());
+    }
+
+    #[test]
+    fn test_expand_then_match_round_trip() {
+        let template = UriTemplate::parse("repo://{owner}/{name}{/path*}").unwrap();
+        let variables = HashMap::from([
+            ("owner".to_string(), "rust lang".into()),
+            ("name".to_string(), "cargo".into()),
+            ("path".to_string(), vec!["src", "lib.rs"].into()),
+        ]);
+        let uri = template.expand(&variables);
+        assert_eq!(uri, "repo://rust%20lang/cargo/src/lib.rs");
+
+        let matched = template.match_uri(&uri).unwrap();
+        assert_eq!(matched["owner"], "rust lang");
+        assert_eq!(matched["name"], "cargo");
+        assert_eq!(matched["path"], "src/lib.rs");
+    }
+}